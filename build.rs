@@ -0,0 +1,20 @@
+//! Check that the `nightly` feature is only enabled on a toolchain accepting unstable features,
+//! so that `--all-features` on stable fails with an explanation rather than with E0554
+use std::env;
+use std::ffi::OsString;
+use std::process::Command;
+
+fn main() {
+	println!("cargo:rerun-if-env-changed=RUSTC_BOOTSTRAP");
+	if env::var_os("CARGO_FEATURE_NIGHTLY").is_none() || env::var_os("RUSTC_BOOTSTRAP").is_some() {
+		return;
+	}
+	let rustc = env::var_os("RUSTC").unwrap_or_else(|| OsString::from("rustc"));
+	let version = match Command::new(rustc).arg("--version").output() {
+		Ok(output) => String::from_utf8_lossy(&output.stdout).into_owned(),
+		Err(_) => return
+	};
+	if !version.contains("-nightly") && !version.contains("-dev") {
+		panic!("the `nightly` feature of exceptions-rs needs a nightly toolchain, but the compiler is {}. Build with `cargo +nightly`, or enable every feature but `nightly` instead of `--all-features`", version.trim());
+	}
+}
@@ -0,0 +1,111 @@
+//! Command line tool pretty-printing exceptions serialized by the `json` module. The input can be
+//! a JSON document, or a log where each line may contain a serialized exception. Run it with
+//! `cargo run --example pretty --features json -- [FILE...]`
+// `Result<T, Exception>` is how the crate returns failures
+#![allow(clippy::result_large_err)]
+extern crate exceptions;
+use std::env;
+use std::fs::File;
+use std::io::{stdin, stdout, stderr, Read, Write};
+use std::process;
+use exceptions::{Throwable, IntoThrowable, Exception};
+use exceptions::format::{Formatter, Style};
+
+const USAGE: &str = "Usage: pretty [--style full|python|concise|verbose|flat|key-value|logfmt|markdown|json] [--color] [--max-frames N] [--width N] [FILE...]
+
+Pretty-print exceptions serialized as JSON, read from the files or from stdin.";
+
+/// Parse the command line arguments, or get the usage error to print
+fn parse_args(args: &[String]) -> Result<(Formatter, Vec<String>), String> {
+	let mut formatter = Formatter::new();
+	let mut files = Vec::new();
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--style" => match args.next().map(|s| s.as_str()) {
+				Some("full") => formatter = formatter.style(Style::Full),
+				Some("python") => formatter = formatter.style(Style::Python),
+				Some("concise") => formatter = formatter.style(Style::Concise),
+				Some("verbose") => formatter = formatter.style(Style::Verbose),
+				Some("flat") => formatter = formatter.style(Style::Flat),
+				Some("key-value") => formatter = formatter.style(Style::KeyValue),
+				Some("logfmt") => formatter = formatter.style(Style::Logfmt),
+				Some("markdown") => formatter = formatter.style(Style::Markdown),
+				Some("json") => formatter = formatter.style(Style::Json),
+				_ => return Err("--style expects full, python, concise, verbose, flat, key-value, logfmt, markdown or json".to_string())
+			},
+			"--color" => formatter = formatter.color(true),
+			"--max-frames" => match args.next().and_then(|s| s.parse().ok()) {
+				Some(max) => formatter = formatter.max_frames(max),
+				None => return Err("--max-frames expects a number".to_string())
+			},
+			"--width" => match args.next().and_then(|s| s.parse().ok()) {
+				Some(width) => formatter = formatter.width(width),
+				None => return Err("--width expects a number".to_string())
+			},
+			"-h" | "--help" => {
+				println!("{}", USAGE);
+				process::exit(0);
+			},
+			_ => files.push(arg.clone())
+		}
+	}
+	return Ok((formatter, files));
+}
+
+fn read_input(files: &[String]) -> Result<String, Exception> {
+	let mut input = String::new();
+	if files.is_empty() {
+		exceptions::try!(stdin().read_to_string(&mut input));
+	}
+	for file in files {
+		let mut f = exceptions::try!(File::open(file));
+		exceptions::try!(f.read_to_string(&mut input));
+		input.push('\n');
+	}
+	return Ok(input);
+}
+
+/// Find the serialized exceptions in `input`. It's first parsed as a whole, then line by line
+/// from the first `{` of each line
+fn find_exceptions(input: &str) -> Vec<Exception> {
+	if let Ok(e) = exceptions::json::from_json(input) {
+		return vec!(e);
+	}
+	return input.lines()
+		.filter_map(|l| l.find('{').map(|i| &l[i..]))
+		.filter_map(|l| exceptions::json::from_json(l).ok())
+		.collect();
+}
+
+fn run(formatter: &Formatter, files: &[String]) -> Result<(), Exception> {
+	let input = exceptions::try!(read_input(files));
+	let found = find_exceptions(&input);
+	if found.is_empty() {
+		return Err(Exception::new("No serialized exception found in input".to_string()));
+	}
+	let out = stdout();
+	let mut out = out.lock();
+	for (i, e) in found.iter().enumerate() {
+		if i > 0 {
+			exceptions::try!(writeln!(out, ""));
+		}
+		exceptions::try!(formatter.write(e, &mut out));
+	}
+	return Ok(());
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let (formatter, files) = match parse_args(&args) {
+		Ok(parsed) => parsed,
+		Err(message) => {
+			let _ = writeln!(stderr(), "{}\n\n{}", message, USAGE);
+			process::exit(2);
+		}
+	};
+	if let Err(e) = run(&formatter, &files) {
+		e.print_stack_trace();
+		process::exit(1);
+	}
+}
@@ -0,0 +1,483 @@
+//! Procedural macros for the `exceptions` crate, re-exported by it when its `macros` feature is
+//! enabled
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::{parse_macro_input, parse_quote, parse_quote_spanned, Attribute, Data, DeriveInput, Expr, Fields, Ident, ItemFn, LitStr, Macro, Path, ReturnType, Token};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
+
+/// Arguments of `#[throws]`: the declared exception types, optionally followed by `; checked`
+struct ThrowsArgs {
+	types: Vec<Path>,
+	checked: bool
+}
+
+impl Parse for ThrowsArgs {
+	fn parse(input: ParseStream) -> syn::Result<ThrowsArgs> {
+		let mut types = Vec::new();
+		while !input.is_empty() && !input.peek(Token![;]) {
+			types.push(input.parse()?);
+			if !input.peek(Token![,]) {
+				break;
+			}
+			input.parse::<Token![,]>()?;
+		}
+		let mut checked = false;
+		if input.peek(Token![;]) {
+			input.parse::<Token![;]>()?;
+			let mode: Ident = input.parse()?;
+			if mode != "checked" {
+				return Err(syn::Error::new(mode.span(), "expected `checked`"));
+			}
+			checked = true;
+		}
+		if !input.is_empty() {
+			return Err(input.error("unexpected tokens in #[throws(...)]"));
+		}
+		return Ok(ThrowsArgs{types: types, checked: checked});
+	}
+}
+
+/// Turn a function returning `T` into one returning `Result<T, E>`. The value of the body and of
+/// `return` expressions are wrapped in `Ok`, so errors are raised with `throw!` and `try!`.
+/// 
+/// The error type `E` is `Exception` when no type is declared, the declared type when there is
+/// one, and `BoxedThrowable` when there are several ones. In that case the `throw!` and `try!`
+/// macros of the body box their errors, which have to be `Throwable`s.
+/// 
+/// With `; checked` after the declared types, the values given to `throw!` are verified to be of
+/// one of the declared types, when their type can be told from the syntax: a string literal or
+/// `format!`/`exception!` invocation is an `Exception`, and `Type::function(...)`, `Type(...)` or
+/// `Type { ... }` are of type `Type`. It's best-effort: the errors propagated through `try!` are
+/// not verified.
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::io;
+/// # use exceptions::prelude::*;
+/// # use exceptions::throws;
+/// # throwable_error! {
+/// #     #[derive(Debug)]
+/// #     pub struct IoException(io::Error);
+/// # }
+/// # pub struct ParseException { message: &'static str }
+/// # impl ParseException {
+/// #     fn new(message: &'static str) -> ParseException { return ParseException{message: message}; }
+/// # }
+/// # impl Throwable for ParseException {
+/// #     fn push_entry(&mut self, _entry: StackEntry) {}
+/// #     fn get_message(&self) -> &str { return self.message; }
+/// #     fn get_cause(&self) -> Option<&Throwable> { return None; }
+/// # }
+/// # pub struct Config;
+/// # fn read(_path: &str) -> Result<String, IoException> { return Ok(String::new()); }
+/// # fn parse(_text: &str) -> Result<Config, ParseException> { return Ok(Config); }
+/// #[throws(IoException, ParseException; checked)]
+/// fn load(path: &str) -> Config {
+///     let text = try!(read(path));
+///     if text.is_empty() {
+///         throw!(ParseException::new("Empty configuration"));
+///     }
+///     try!(parse(&text))
+/// }
+/// # fn main() {
+/// # assert_eq!(load("config.toml").err().unwrap().get_message(), "Empty configuration");
+/// # }
+/// ```
+#[proc_macro_attribute]
+pub fn throws(args: TokenStream, item: TokenStream) -> TokenStream {
+	let args = parse_macro_input!(args as ThrowsArgs);
+	let mut function = parse_macro_input!(item as ItemFn);
+	
+	let boxed = args.types.len() > 1;
+	let error: syn::Type = match args.types.len() {
+		0 => parse_quote!(::exceptions::Exception),
+		1 => syn::Type::Path(syn::TypePath{qself: None, path: args.types[0].clone()}),
+		_ => parse_quote!(::exceptions::BoxedThrowable)
+	};
+	let declared: Vec<String> = match args.types.len() {
+		0 => vec!["Exception".to_string()],
+		_ => args.types.iter().map(last_segment).collect()
+	};
+	
+	let mut rewriter = BodyRewriter{boxed: boxed, checked: args.checked, declared: declared, errors: Vec::new()};
+	rewriter.visit_block_mut(&mut function.block);
+	// Reported with `compile_error!` rather than `syn::Error`, whose expansion refers to `::core`
+	// which isn't in scope of crates using the 2015 edition without declaring it
+	let errors: Vec<proc_macro2::TokenStream> = rewriter.errors.iter().map(|&(span, ref message)| {
+		quote_spanned!(span=> compile_error!(#message);)
+	}).collect();
+	
+	let value: syn::Type = match function.sig.output {
+		ReturnType::Default => parse_quote!(()),
+		ReturnType::Type(_, ref ty) => (**ty).clone()
+	};
+	function.sig.output = parse_quote!(-> ::std::result::Result<#value, #error>);
+	let block = &function.block;
+	*function.block = parse_quote!({ ::std::result::Result::Ok(#block) });
+	function.attrs.push(parse_quote!(#[allow(unreachable_code)]));
+	return quote!(#(#errors)* #function).into();
+}
+
+/// Rewrite the body of a `#[throws]` function: wrap the returned values in `Ok`, box the errors
+/// of `throw!` and `try!` if needed, and check the thrown types. Closures and nested items are
+/// left untouched, since they return from themselves
+struct BodyRewriter {
+	boxed: bool,
+	checked: bool,
+	declared: Vec<String>,
+	errors: Vec<(Span, String)>
+}
+
+impl VisitMut for BodyRewriter {
+	fn visit_expr_mut(&mut self, expr: &mut Expr) {
+		match *expr {
+			Expr::Closure(..) | Expr::Async(..) => return,
+			Expr::Return(ref mut ret) => {
+				if let Some(ref mut value) = ret.expr {
+					self.visit_expr_mut(value);
+				}
+				let value = match ret.expr.take() {
+					Some(value) => quote!(#value),
+					None => quote!(())
+				};
+				ret.expr = Some(Box::new(parse_quote!(::std::result::Result::Ok(#value))));
+				return;
+			},
+			_ => {}
+		}
+		visit_mut::visit_expr_mut(self, expr);
+	}
+	
+	fn visit_item_mut(&mut self, _item: &mut syn::Item) {}
+	
+	fn visit_macro_mut(&mut self, mac: &mut Macro) {
+		let name = last_segment(&mac.path);
+		let kind = match name.trim_start_matches("r#") {
+			"throw" => quote!(throw),
+			"try" => quote!(propagate),
+			_ => return
+		};
+		if name == "throw" && self.checked {
+			self.check_thrown(mac);
+		}
+		if self.boxed {
+			// Keep the span of the invocation, so that the recorded line is the one of the body
+			let span = mac.path.segments.last().map(|s| s.ident.span()).unwrap_or_else(Span::call_site);
+			let tokens = &mac.tokens;
+			mac.path = parse_quote_spanned!(span=> ::exceptions::__throws_boxed);
+			mac.tokens = quote!(#kind #tokens);
+		}
+	}
+}
+
+impl BodyRewriter {
+	/// Check the type of the value thrown by a `throw!` invocation, if it can be told
+	fn check_thrown(&mut self, mac: &Macro) {
+		// Ignore the context values following the thrown value
+		let tokens: proc_macro2::TokenStream = mac.tokens.clone().into_iter().take_while(|t| match *t {
+			TokenTree::Punct(ref p) => p.as_char() != ';',
+			_ => true
+		}).collect();
+		let thrown: Expr = match syn::parse2(tokens) {
+			Ok(thrown) => thrown,
+			Err(_) => return
+		};
+		if let Some(ty) = thrown_type(&thrown) {
+			if !self.declared.contains(&ty) {
+				let message = format!("`{}` is thrown but not declared in #[throws({})]", ty, self.declared.join(", "));
+				self.errors.push((thrown.span(), message));
+			}
+		}
+	}
+}
+
+/// Tell the type of a thrown value from its syntax, if possible
+fn thrown_type(expr: &Expr) -> Option<String> {
+	return match *expr {
+		// String literals are left unchecked, since `throw!` converts them into the declared type
+		Expr::Macro(ref mac) => match last_segment(&mac.mac.path).as_str() {
+			"format" | "exception" => Some("Exception".to_string()),
+			_ => None
+		},
+		Expr::Call(ref call) => match *call.func {
+			Expr::Path(ref path) if path.path.segments.len() > 1 => {
+				let segments = &path.path.segments;
+				Some(segments[segments.len() - 2].ident.to_string())
+			},
+			Expr::Path(ref path) => {
+				let name = last_segment(&path.path);
+				if name.starts_with(char::is_uppercase) { Some(name) } else { None }
+			},
+			_ => None
+		},
+		Expr::Struct(ref st) => Some(last_segment(&st.path)),
+		Expr::MethodCall(ref call) => thrown_type(&call.receiver),
+		Expr::Paren(ref paren) => thrown_type(&paren.expr),
+		_ => None
+	};
+}
+
+fn last_segment(path: &Path) -> String {
+	return path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default();
+}
+
+/// Implement `IntoThrowable<Exception>` for an enum. Each variant has a `#[message("...")]`
+/// attribute, formatted with the fields of the variant: `{name}` for a named field, or `{0}` for
+/// the first field of a tuple variant. A `#[kind("...")]` attribute sets the kind of the
+/// `Exception`, on a variant, or on the enum for the variants without one. The variant is
+/// recorded in the `Exception`, for `Exception::variant_of` and `catch_kinds!`.
+/// 
+/// `From<Enum>` is implemented for `Exception`, recording where `?` converted the enum as a
+/// stack trace entry. A `#[from]` attribute on a variant with a single field implements `From`
+/// the type of this field for the enum, so that `?` works in functions returning either.
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::num::ParseIntError;
+/// # use exceptions::prelude::*;
+/// # use exceptions::IntoThrowable;
+/// #[derive(IntoThrowable)]
+/// #[kind("config")]
+/// enum ConfigError {
+///     #[message("Cannot read {path}")]
+///     #[kind("config.io")]
+///     Read { path: String },
+///     #[message("Invalid value at line {0}")]
+///     Invalid(u32),
+///     #[message("Cannot parse: {0}")]
+///     #[from]
+///     Parse(ParseIntError)
+/// }
+/// # fn main() {
+/// # let e: Exception = ConfigError::Read{path: "config.toml".to_string()}.into_throwable();
+/// # assert_eq!(e.get_message(), "Cannot read config.toml");
+/// # assert!(e.is_instance_of("config.io"));
+/// # assert_eq!(e.variant_of::<ConfigError>(), Some("Read"));
+/// # }
+/// ```
+#[proc_macro_derive(IntoThrowable, attributes(message, kind, from))]
+pub fn derive_into_throwable(item: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(item as DeriveInput);
+	return match into_throwable_impl(&input) {
+		Ok(tokens) => tokens.into(),
+		Err((span, message)) => quote_spanned!(span=> compile_error!(#message);).into()
+	};
+}
+
+fn into_throwable_impl(input: &DeriveInput) -> Result<proc_macro2::TokenStream, (Span, String)> {
+	let variants = match input.data {
+		Data::Enum(ref data) => &data.variants,
+		_ => return Err((input.ident.span(), "#[derive(IntoThrowable)] is only supported on enums".to_string()))
+	};
+	let default_kind = string_attribute(&input.attrs, "kind")?;
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let mut arms = Vec::new();
+	let mut froms = Vec::new();
+	for variant in variants {
+		let message = match string_attribute(&variant.attrs, "message")? {
+			Some(message) => message,
+			None => return Err((variant.ident.span(), format!("missing #[message(\"...\")] on variant `{}`", variant.ident)))
+		};
+		let kind = match string_attribute(&variant.attrs, "kind")? {
+			Some(kind) => Some(kind),
+			None => default_kind.clone()
+		};
+		let kind = match kind {
+			Some(kind) => quote!(::std::option::Option::Some(#kind)),
+			None => quote!(::std::option::Option::None)
+		};
+		let ident = &variant.ident;
+		if let Some(attr) = variant.attrs.iter().find(|a| a.path().is_ident("from")) {
+			if variant.fields.len() != 1 {
+				return Err((attr.span(), format!("#[from] requires variant `{}` to have a single field", ident)));
+			}
+			let field = variant.fields.iter().next().unwrap();
+			let ty = &field.ty;
+			let construct = match field.ident {
+				Some(ref field) => quote!(#name::#ident { #field: source }),
+				None => quote!(#name::#ident(source))
+			};
+			froms.push(quote!(
+				impl #impl_generics ::std::convert::From<#ty> for #name #ty_generics #where_clause {
+					fn from(source: #ty) -> #name #ty_generics {
+						return #construct;
+					}
+				}
+			));
+		}
+		let pattern = match variant.fields {
+			Fields::Named(ref fields) => {
+				let names = fields.named.iter().map(|f| &f.ident);
+				quote!(#name::#ident { #(ref #names),* })
+			},
+			Fields::Unnamed(ref fields) => {
+				let names = (0..fields.unnamed.len()).map(|i| Ident::new(&format!("_{}", i), Span::call_site()));
+				quote!(#name::#ident(#(ref #names),*))
+			},
+			Fields::Unit => quote!(#name::#ident)
+		};
+		let message = LitStr::new(&positional_to_named(&message.value()), message.span());
+		let variant_name = ident.to_string();
+		arms.push(quote!(#pattern => (format!(#message), #kind, #variant_name)));
+	}
+	return Ok(quote!(
+		impl #impl_generics ::exceptions::IntoThrowable<::exceptions::Exception> for #name #ty_generics #where_clause {
+			#[allow(unused_variables)]
+			fn into_throwable(self) -> ::exceptions::Exception {
+				let (message, kind, variant): (::std::string::String, ::std::option::Option<&'static str>, &'static str) = match self {
+					#(#arms,)*
+				};
+				let mut e = ::exceptions::Exception::new(message);
+				e.set_variant(::std::any::type_name::<Self>(), variant);
+				if let ::std::option::Option::Some(kind) = kind {
+					e.set_kind(kind);
+				}
+				return e;
+			}
+		}
+		
+		impl #impl_generics ::std::convert::From<#name #ty_generics> for ::exceptions::Exception #where_clause {
+			#[track_caller]
+			fn from(e: #name #ty_generics) -> ::exceptions::Exception {
+				let location = ::std::panic::Location::caller();
+				let mut th: ::exceptions::Exception = ::exceptions::IntoThrowable::into_throwable(e);
+				::exceptions::thrown(&mut th);
+				::exceptions::Throwable::push_stack(&mut th, location.file(), location.line(), "?");
+				return th;
+			}
+		}
+		
+		#(#froms)*
+	));
+}
+
+/// Get the string given to the attribute `name`, like `#[name("value")]`, if there is one
+fn string_attribute(attrs: &[Attribute], name: &str) -> Result<Option<LitStr>, (Span, String)> {
+	for attr in attrs {
+		if attr.path().is_ident(name) {
+			return match attr.parse_args::<LitStr>() {
+				Ok(value) => Ok(Some(value)),
+				Err(_) => Err((attr.span(), format!("expected #[{}(\"...\")]", name)))
+			};
+		}
+	}
+	return Ok(None);
+}
+
+/// Rewrite the positional arguments of a format string, like `{0}` or `{1:?}`, into the names
+/// given to the fields of tuple variants, like `{_0}` or `{_1:?}`
+fn positional_to_named(format: &str) -> String {
+	let mut out = String::new();
+	let mut chars = format.chars().peekable();
+	while let Some(c) = chars.next() {
+		out.push(c);
+		if c != '{' {
+			continue;
+		}
+		if chars.peek() == Some(&'{') {
+			out.push(chars.next().unwrap());
+			continue;
+		}
+		if chars.peek().is_some_and(|c| c.is_ascii_digit()) {
+			out.push('_');
+		}
+	}
+	return out;
+}
+
+/// Like the `try!` macro of `exceptions`, but record the exact source text of the expression,
+/// with its column range, and the line where it starts, instead of `stringify!`-ing it. This
+/// gives cleaner stack trace entries for long or multi-line expressions.
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # use exceptions::try_spanned;
+/// # struct Users;
+/// # impl Users {
+/// #     fn find(&self, id: u32) -> Result<String, Exception> { throw!(format!("Unknown user {}", id)); }
+/// # }
+/// # struct Db;
+/// # impl Db {
+/// #     fn users(&self) -> Users { return Users; }
+/// # }
+/// # fn load(db: Db, id: u32) -> Result<String, Exception> {
+/// let user = try_spanned!(db.users()
+///     .find(id));
+/// // at db.users() .find(id) (columns 24 to 3:11) [src/users.rs:12]
+/// # return Ok(user);
+/// # }
+/// # fn main() {
+/// # assert!(load(Db, 7).unwrap_err().frames().next().unwrap().expr.starts_with("db.users() .find(id)"));
+/// # }
+/// ```
+#[proc_macro]
+pub fn try_spanned(input: TokenStream) -> TokenStream {
+	return spanned(input, "propagate", "", "");
+}
+
+/// Like the `throw!` macro of `exceptions`, but record the exact source text of the thrown
+/// value, with its column range, and the line where it starts
+#[proc_macro]
+pub fn throw_spanned(input: TokenStream) -> TokenStream {
+	return spanned(input, "throw", "throw!(", ")");
+}
+
+fn spanned(input: TokenStream, kind: &str, prefix: &str, suffix: &str) -> TokenStream {
+	let tokens: Vec<proc_macro::TokenTree> = input.clone().into_iter().collect();
+	let first = match tokens.first() {
+		Some(first) => first.span(),
+		None => return quote!(compile_error!("expected an expression");).into()
+	};
+	let last = tokens[tokens.len() - 1].span();
+	let expr = proc_macro2::TokenStream::from(input);
+	if let Err(e) = syn::parse2::<Expr>(expr.clone()) {
+		let message = e.to_string();
+		return quote_spanned!(e.span()=> compile_error!(#message);).into();
+	}
+	let (start, end) = (first.start(), last.end());
+	let columns = if start.line() == end.line() {
+		format!("columns {}-{}", start.column(), end.column())
+	} else {
+		format!("columns {} to {}:{}", start.column(), end.line(), end.column())
+	};
+	let text = format!("{}{}{} ({})", prefix, source_text(&tokens), suffix, columns);
+	let line = start.line() as u32;
+	let kind = Ident::new(kind, Span::call_site());
+	return quote!(::exceptions::__exceptions_spanned!(#kind #line, #text; #expr)).into();
+}
+
+/// Get the source text of `tokens`, keeping the spaces between tokens of a same line, and
+/// replacing line breaks by a space
+fn source_text(tokens: &[proc_macro::TokenTree]) -> String {
+	let mut text = String::new();
+	let mut previous: Option<proc_macro::Span> = None;
+	for token in tokens {
+		let span = token.span();
+		if let Some(previous) = previous {
+			let (end, start) = (previous.end(), span.start());
+			if end.line() != start.line() {
+				text.push(' ');
+			} else {
+				for _ in end.column()..start.column() {
+					text.push(' ');
+				}
+			}
+		}
+		match span.source_text() {
+			Some(source) => text.push_str(&source),
+			None => text.push_str(&token.to_string())
+		}
+		previous = Some(span);
+	}
+	return text;
+}
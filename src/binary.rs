@@ -0,0 +1,183 @@
+//! Compact binary encoding of exceptions, to send them through channels or shared memory where
+//! `Exception`s themselves can't go. Messages, kinds, stack trace entries, context values and
+//! causes are kept.
+//! 
+//! An encoded exception starts with the `MAGIC` bytes, followed by its outermost level. A level
+//! is its message, its kind (empty if none), its stack trace entries as a count followed by the
+//! file, line, expression and repeat count of each one, its context values as a count followed
+//! by keys and values, then 1 followed by its cause, or 0. Strings are a length followed by UTF-8
+//! bytes, and numbers are LEB128 variable length integers. Decoding rejects exceptions having
+//! more than `MAX_DEPTH` levels
+//! 
+//! ```
+//! # #[macro_use] extern crate exceptions;
+//! # use std::sync::mpsc;
+//! # use exceptions::prelude::*;
+//! # use exceptions::binary;
+//! # fn receive() -> Result<(), Exception> {
+//! # let (sender, receiver) = mpsc::channel();
+//! # let e = binary::decode(&binary::encode(&Exception::new("Cannot fetch".to_string()))).unwrap();
+//! sender.send(binary::encode(&e)).unwrap();
+//! let e = try!(binary::decode(&receiver.recv().unwrap()));
+//! # throw!(e);
+//! # }
+//! # assert_eq!(receive().unwrap_err().get_message(), "Cannot fetch");
+//! ```
+use std::borrow::Cow;
+use {Throwable, Exception, StackEntry, StackTrace};
+
+/// First bytes of an encoded exception, ending with the version of the encoding
+pub const MAGIC: &[u8] = b"EXC\x01";
+
+/// Maximum number of levels of a decoded exception, so that malformed input can't build chains
+/// of causes deep enough to overflow the stack when they're formatted or dropped
+pub const MAX_DEPTH: usize = 256;
+
+/// Encode `th` and its causes
+pub fn encode<T: Throwable+?Sized>(th: &T) -> Vec<u8> {
+	let mut out = MAGIC.to_vec();
+	encode_level(th, &mut out);
+	return out;
+}
+
+fn encode_level<T: Throwable+?Sized>(th: &T, out: &mut Vec<u8>) {
+	write_str(th.get_message(), out);
+	write_str(th.kind(), out);
+	write_number(th.frame_count() as u64, out);
+	for f in th.frames() {
+		write_str(&f.file, out);
+		write_number(f.line as u64, out);
+		write_str(&f.expr, out);
+		write_number(f.repeat as u64, out);
+	}
+	write_number(th.get_context().len() as u64, out);
+	for (key, value) in th.get_context() {
+		write_str(key, out);
+		write_str(value, out);
+	}
+	match th.get_cause() {
+		Some(cause) => {
+			out.push(1);
+			encode_level(cause, out);
+		},
+		None => out.push(0)
+	}
+}
+
+fn write_number(mut n: u64, out: &mut Vec<u8>) {
+	while n >= 0x80 {
+		out.push((n as u8 & 0x7f) | 0x80);
+		n >>= 7;
+	}
+	out.push(n as u8);
+}
+
+fn write_str(s: &str, out: &mut Vec<u8>) {
+	write_number(s.len() as u64, out);
+	out.extend_from_slice(s.as_bytes());
+}
+
+/// Decode an `Exception` and its causes encoded by `encode`
+pub fn decode(bytes: &[u8]) -> Result<Exception, Exception> {
+	if !bytes.starts_with(MAGIC) {
+		return Err(Exception::new("Invalid encoded exception: unknown format".to_string()));
+	}
+	let mut reader = Reader{bytes: bytes, pos: MAGIC.len()};
+	// Levels are read from the outermost one, then chained from the innermost one
+	let mut levels = Vec::new();
+	loop {
+		if levels.len() == MAX_DEPTH {
+			return Err(Exception::new(format!("Invalid encoded exception: more than {} levels", MAX_DEPTH)));
+		}
+		let (e, has_cause) = decode_level(&mut reader)?;
+		levels.push(e);
+		if !has_cause {
+			break;
+		}
+	}
+	if reader.pos != bytes.len() {
+		return Err(Exception::new("Invalid encoded exception: trailing bytes".to_string()));
+	}
+	let mut e = levels.pop().unwrap();
+	while let Some(mut outer) = levels.pop() {
+		outer.cause = Some(Box::new(e));
+		e = outer;
+	}
+	return Ok(e);
+}
+
+/// Decode a level without its cause, telling if a cause follows
+fn decode_level(reader: &mut Reader) -> Result<(Exception, bool), Exception> {
+	let message = reader.read_str()?.to_string();
+	let kind = reader.read_str()?;
+	let mut entries = Vec::new();
+	for _ in 0..reader.read_number()? {
+		let file = reader.read_str()?.to_string();
+		let line = reader.read_number()? as u32;
+		let expr = reader.read_str()?.to_string();
+		let repeat = reader.read_number()? as u32;
+		entries.push(StackEntry{file: Cow::Owned(file), line: line, expr: Cow::Owned(expr), repeat: repeat});
+	}
+	let mut context = Vec::new();
+	for _ in 0..reader.read_number()? {
+		let key = reader.read_str()?.to_string();
+		context.push((key, reader.read_str()?.to_string()));
+	}
+	let has_cause = match reader.read_byte()? {
+		0 => false,
+		1 => true,
+		_ => return Err(Exception::new("Invalid encoded exception: bad cause marker".to_string()))
+	};
+	let mut e = Exception::new(message);
+	if !kind.is_empty() {
+		e.set_kind(kind);
+	}
+	e.stack = StackTrace::from(entries);
+	e.context = context;
+	return Ok((e, has_cause));
+}
+
+struct Reader<'a> {
+	bytes: &'a [u8],
+	pos: usize
+}
+
+impl <'a> Reader<'a> {
+	fn read_byte(&mut self) -> Result<u8, Exception> {
+		let b = match self.bytes.get(self.pos) {
+			Some(&b) => b,
+			None => return Err(Exception::new("Invalid encoded exception: truncated".to_string()))
+		};
+		self.pos += 1;
+		return Ok(b);
+	}
+	
+	fn read_number(&mut self) -> Result<u64, Exception> {
+		let mut n: u64 = 0;
+		let mut shift = 0;
+		loop {
+			let b = self.read_byte()?;
+			if shift >= 64 {
+				return Err(Exception::new("Invalid encoded exception: number overflow".to_string()));
+			}
+			n |= ((b & 0x7f) as u64) << shift;
+			if b & 0x80 == 0 {
+				return Ok(n);
+			}
+			shift += 7;
+		}
+	}
+	
+	fn read_str(&mut self) -> Result<&'a str, Exception> {
+		let len = self.read_number()? as usize;
+		if len > self.bytes.len() - self.pos {
+			return Err(Exception::new("Invalid encoded exception: truncated".to_string()));
+		}
+		let bytes = &self.bytes[self.pos..self.pos + len];
+		self.pos += len;
+		return match ::std::str::from_utf8(bytes) {
+			Ok(s) => Ok(s),
+			Err(_) => Err(Exception::new("Invalid encoded exception: invalid UTF-8".to_string()))
+		};
+	}
+}
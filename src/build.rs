@@ -0,0 +1,37 @@
+//! Information about the build of the application, stated in rendered traces
+use std::fmt;
+use std::sync::RwLock;
+
+/// Identify the build of an application
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BuildInfo {
+	/// The name of the crate
+	pub name: &'static str,
+	/// The version of the crate
+	pub version: &'static str,
+	/// The version control revision the crate was built from, if known
+	pub revision: Option<&'static str>
+}
+
+impl fmt::Display for BuildInfo {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{} {}", self.name, self.version)?;
+		if let Some(revision) = self.revision {
+			write!(f, " ({})", revision)?;
+		}
+		return Ok(());
+	}
+}
+
+static BUILD_INFO: RwLock<Option<BuildInfo>> = RwLock::new(None);
+
+/// Set the build information rendered at the end of traces. Code should instead call
+/// the `build_info!` macro
+pub fn set_build_info(info: BuildInfo) {
+	*BUILD_INFO.write().unwrap_or_else(|e| e.into_inner()) = Some(info);
+}
+
+/// Get the build information set with `set_build_info`, if any
+pub fn build_info() -> Option<BuildInfo> {
+	return *BUILD_INFO.read().unwrap_or_else(|e| e.into_inner());
+}
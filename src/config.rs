@@ -0,0 +1,81 @@
+//! Global configuration of the rendering of stack traces, set once at startup and used by
+//! `Throwable::print_stack_trace` and the other default renderings, like the sinks and `Report`.
+//! Until it's set, the style can be chosen with the `EXCEPTIONS_STYLE` environment variable,
+//! among `full`, `compact` and, with the `json` feature, `json`
+//! 
+//! ```
+//! use exceptions::config::{self, FormatConfig};
+//! use exceptions::format::Style;
+//! 
+//! config::set(FormatConfig{style: Style::Concise, max_frames: Some(10), ..FormatConfig::default()});
+//! ```
+use std::env;
+use std::sync::RwLock;
+use format::{Style, Labels};
+
+/// Settings of the default renderings
+#[derive(Clone, Debug)]
+pub struct FormatConfig {
+	/// Layout of the rendered traces
+	pub style: Style,
+	/// Whether ANSI colors are used. They are still disabled when stderr isn't a terminal, or
+	/// if `NO_COLOR` is set or `CLICOLOR` is `0`
+	pub color: bool,
+	/// Use colors even if stderr isn't a terminal or the environment disables them
+	pub force_color: bool,
+	/// Maximum number of stack trace entries rendered per `Throwable`
+	pub max_frames: Option<usize>,
+	/// Prefixes removed from the start of the files of stack trace entries
+	pub path_strip: Vec<String>,
+	/// Prefixes of the files of the application code, the other ones being dependency code
+	pub app_paths: Vec<String>,
+	/// Whether consecutive stack trace entries of dependency code are collapsed into one line
+	pub collapse_dependencies: bool,
+	/// Whether a section with the operating system, the architecture and the hostname is appended
+	pub system_info: bool,
+	/// Fixed texts of rendered traces
+	pub labels: Labels
+}
+
+/// The settings of `format::Formatter::new()`
+impl Default for FormatConfig {
+	fn default() -> FormatConfig {
+		return FormatConfig{style: Style::Full, color: false, force_color: false, max_frames: None, path_strip: Vec::new(), app_paths: Vec::new(), collapse_dependencies: false, system_info: false, labels: Labels::default()};
+	}
+}
+
+static CONFIG: RwLock<Option<FormatConfig>> = RwLock::new(None);
+
+/// Set the global configuration, replacing the current one
+pub fn set(config: FormatConfig) {
+	*CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+}
+
+/// Get the global configuration. If it's not set, it's the default one, with the style given by
+/// the `EXCEPTIONS_STYLE` environment variable if any
+pub fn get() -> FormatConfig {
+	if let Some(ref config) = *CONFIG.read().unwrap_or_else(|e| e.into_inner()) {
+		return config.clone();
+	}
+	let mut config = FormatConfig::default();
+	if let Some(style) = env::var("EXCEPTIONS_STYLE").ok().and_then(|s| style_from_env(&s)) {
+		config.style = style;
+	}
+	return config;
+}
+
+/// Get the style named `name` in `EXCEPTIONS_STYLE`. Unknown names are ignored
+fn style_from_env(name: &str) -> Option<Style> {
+	return match name.trim() {
+		"full" => Some(Style::Full),
+		"compact" => Some(Style::Concise),
+		#[cfg(feature = "json")]
+		"json" => Some(Style::Json),
+		_ => None
+	};
+}
+
+/// Restore the default configuration
+pub fn reset() {
+	*CONFIG.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
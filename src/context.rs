@@ -0,0 +1,104 @@
+//! Context labels attached to the exceptions thrown in a scope, like the request being
+//! handled, without passing them down to every function
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use Throwable;
+
+/// Key of the context values holding the labels
+pub const CONTEXT_KEY: &str = "context";
+
+thread_local!(static LABELS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) });
+
+/// Guard making `label` active on the current thread until dropped. Each `Throwable` thrown or
+/// propagated by the macros while labels are active records them as `context` values, from the
+/// outermost to the innermost one, the first time it's thrown. Dropping a guard also deactivates the labels of the guards entered after it
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # use exceptions::context::ContextGuard;
+/// # fn handle(_request: &str) -> Result<(), Exception> { throw!("Cannot read"); }
+/// # fn serve(id: u32, request: &str) -> Result<(), Exception> {
+/// let _context = ContextGuard::enter(&format!("request {}", id));
+/// try!(handle(request));
+/// # return Ok(());
+/// # }
+/// # assert_eq!(serve(7, "GET /").unwrap_err().get_context()[0].1, "request 7");
+/// ```
+pub struct ContextGuard {
+	/// Number of active labels before this one
+	depth: usize,
+	// The labels belong to a thread
+	_not_send: PhantomData<*const ()>
+}
+
+impl ContextGuard {
+	/// Make `label` active on the current thread
+	pub fn enter(label: &str) -> ContextGuard {
+		let depth = LABELS.with(|l| {
+			let mut labels = l.borrow_mut();
+			labels.push(label.to_string());
+			return labels.len() - 1;
+		});
+		return ContextGuard{depth: depth, _not_send: PhantomData};
+	}
+}
+
+impl Drop for ContextGuard {
+	fn drop(&mut self) {
+		let _ = LABELS.try_with(|l| l.borrow_mut().truncate(self.depth));
+	}
+}
+
+/// Get the active labels of the current thread, from the outermost to the innermost one
+pub fn labels() -> Vec<String> {
+	return LABELS.try_with(|l| l.borrow().clone()).unwrap_or_default();
+}
+
+/// Record the active labels of the current thread as context values of `th`
+pub fn attach<T: Throwable+?Sized>(th: &mut T) {
+	let _ = LABELS.try_with(|l| {
+		if let Ok(labels) = l.try_borrow() {
+			for label in labels.iter() {
+				th.add_context(CONTEXT_KEY, label.clone());
+			}
+		}
+	});
+}
+
+/// Future making a label active while it's polled, so that it's attached to the exceptions
+/// thrown by an asynchronous task, whatever the thread polling it
+/// 
+/// ```edition2018
+/// # use exceptions::prelude::*;
+/// # use exceptions::context::with_label;
+/// # async fn handle(_request: &str) -> Result<(), Exception> { return Err(Exception::new("Cannot read".to_string())); }
+/// # async fn serve(id: u32, request: &str) -> Result<(), Exception> {
+/// let response = with_label(format!("request {}", id), handle(request)).await;
+/// # return response;
+/// # }
+/// ```
+pub struct WithLabel<F> {
+	label: String,
+	inner: F
+}
+
+/// Make `label` active while `future` is polled
+pub fn with_label<F: Future>(label: String, future: F) -> WithLabel<F> {
+	return WithLabel{label: label, inner: future};
+}
+
+impl <F: Future> Future for WithLabel<F> {
+	type Output = F::Output;
+	
+	fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<F::Output> {
+		// The inner future is never moved out of the pinned wrapper
+		let this = unsafe { self.get_unchecked_mut() };
+		let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+		let _context = ContextGuard::enter(&this.label);
+		return inner.poll(cx);
+	}
+}
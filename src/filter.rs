@@ -0,0 +1,57 @@
+//! Global filters hiding stack trace entries, like the ones of noisy internal crates or of
+//! generated code. Capture filters are applied when an `Exception` records an entry, which is
+//! then lost, while render filters are applied by `format::Formatter` and keep the stack trace
+//! intact. Boundaries are never filtered
+//! 
+//! ```
+//! use exceptions::filter;
+//! 
+//! filter::add_render_filter(Box::new(|f: &exceptions::StackEntry| !f.file.contains("/generated/")));
+//! ```
+use std::sync::RwLock;
+use StackEntry;
+
+/// Represent a filter of stack trace entries
+pub trait FrameFilter: Send + Sync {
+	/// Check if `f` is kept
+	fn keep(&self, f: &StackEntry) -> bool;
+}
+
+impl <F: Fn(&StackEntry) -> bool + Send + Sync> FrameFilter for F {
+	fn keep(&self, f: &StackEntry) -> bool {
+		return self(f);
+	}
+}
+
+static CAPTURE_FILTERS: RwLock<Vec<Box<FrameFilter>>> = RwLock::new(Vec::new());
+static RENDER_FILTERS: RwLock<Vec<Box<FrameFilter>>> = RwLock::new(Vec::new());
+
+/// Register a filter of the entries recorded by exceptions
+pub fn add_capture_filter(filter: Box<FrameFilter>) {
+	CAPTURE_FILTERS.write().unwrap_or_else(|e| e.into_inner()).push(filter);
+}
+
+/// Register a filter of the rendered entries
+pub fn add_render_filter(filter: Box<FrameFilter>) {
+	RENDER_FILTERS.write().unwrap_or_else(|e| e.into_inner()).push(filter);
+}
+
+/// Unregister all the capture and render filters
+pub fn clear_filters() {
+	CAPTURE_FILTERS.write().unwrap_or_else(|e| e.into_inner()).clear();
+	RENDER_FILTERS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// Check if `f` is kept by all the capture filters
+pub fn captured(f: &StackEntry) -> bool {
+	return keep(&CAPTURE_FILTERS, f);
+}
+
+/// Check if `f` is kept by all the render filters
+pub fn rendered(f: &StackEntry) -> bool {
+	return keep(&RENDER_FILTERS, f);
+}
+
+fn keep(filters: &RwLock<Vec<Box<FrameFilter>>>, f: &StackEntry) -> bool {
+	return f.is_boundary() || filters.read().unwrap_or_else(|e| e.into_inner()).iter().all(|filter| filter.keep(f));
+}
@@ -0,0 +1,1092 @@
+//! Configurable rendering of stack traces. The entries are rendered at their original location
+//! when they are covered by a mapping of the `source_map` module
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cmp;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+use std::ptr;
+use std::sync::Arc;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use {Throwable, StackEntry, DroppedFrames, build, filter, source_map};
+use config::{self, FormatConfig};
+#[cfg(feature = "json")]
+use json;
+
+/// Layout of a rendered stack trace. It's non-exhaustive, since some styles are only available
+/// with features, like `Json`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum Style {
+	/// The layout of `Throwable::print_stack_trace`: the message, followed by one line per
+	/// stack trace entry, then the causes introduced by `Caused by: `
+	Full,
+	/// A layout mimicking Python tracebacks, where the innermost causes are rendered first
+	Python,
+	/// A short layout for end users, with only the message, the innermost stack trace entry and
+	/// the message of the root cause
+	Concise,
+	/// The `Full` layout, preceded by the rendering thread and time, with absolute paths, the
+	/// module of each stack trace entry, guessed from its file, and where each `Throwable` was
+	/// handled, if it's recorded
+	Verbose,
+	/// A single trace merging the stack trace entries of the `Throwable` and of its causes, in
+	/// the order they were recorded. Each entry is prefixed by the index of the `Throwable` it
+	/// belongs to, from 0 for the outermost one, and the messages are listed afterwards
+	Flat,
+	/// A single line of `key=value` pairs, for log pipelines handling lines rather than
+	/// multi-line traces: `ts`, `level`, `msg`, and if available `kind`, `top_frame` with the
+	/// file and line of the innermost entry, and `cause` with the message of the root cause
+	KeyValue,
+	/// A single logfmt line with the whole cause chain. The message, kind, context values and
+	/// stack trace entries of the `Throwable` are the `msg`, `kind`, `context.<key>` and
+	/// `frame.<i>` keys, and the ones of its causes are prefixed by `cause.<i>.`, from 1
+	Logfmt,
+	/// Markdown to paste in issues and chats: the message in bold, the stack trace entries in a
+	/// fenced code block, and each cause quoted one level deeper than its parent
+	Markdown,
+	/// The JSON serialization of the `json` module, on a single line
+	#[cfg(feature = "json")]
+	Json
+}
+
+/// Rendering of a stack trace entry, for example as a hyperlink for terminals supporting them
+/// 
+/// ```
+/// use std::io::{self, Write};
+/// use exceptions::StackEntry;
+/// use exceptions::format::FrameRenderer;
+/// 
+/// struct Hyperlinks;
+/// 
+/// impl FrameRenderer for Hyperlinks {
+///     fn render(&self, f: &StackEntry, out: &mut Write) -> io::Result<()> {
+///         return write!(out, "at {} \x1b]8;;file://{}\x1b\\{}:{}\x1b]8;;\x1b\\", f.expr, f.file, f.file, f.line);
+///     }
+/// }
+/// ```
+pub trait FrameRenderer: Send + Sync {
+	/// Write `f` to `out`, without indentation nor line break. Boundaries aren't rendered with it
+	fn render(&self, f: &StackEntry, out: &mut Write) -> io::Result<()>;
+}
+
+/// `FrameRenderer` shared by the clones of a `Formatter`
+#[derive(Clone)]
+struct SharedFrameRenderer(Arc<FrameRenderer>);
+
+impl fmt::Debug for SharedFrameRenderer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return f.write_str("FrameRenderer");
+	}
+}
+
+/// Fixed texts of rendered traces, which can be translated. The `{}` in `more`, `omitted`,
+/// `repeated`, `suppressed` and `dependencies` is replaced by a number
+#[derive(Clone, Debug)]
+pub struct Labels {
+	/// Prefix of the message of a cause
+	pub caused_by: String,
+	/// Word before the expression of a stack trace entry
+	pub at: String,
+	/// Line replacing the stack trace entries elided because of `Formatter::max_frames`
+	pub more: String,
+	/// Line replacing the stack trace entries dropped by the `Throwable` itself
+	pub omitted: String,
+	/// Text appended to a stack trace entry repeated several times
+	pub repeated: String,
+	/// Words before where a `Throwable` was handled, with `Style::Verbose`
+	pub handled_by: String,
+	/// Message of the trace written by `sink::RateLimitedSink` for suppressed exceptions
+	pub suppressed: String,
+	/// Line replacing consecutive dependency entries, with `Formatter::collapse_dependencies`
+	pub dependencies: String
+}
+
+/// English texts
+impl Default for Labels {
+	fn default() -> Labels {
+		return Labels{
+			caused_by: "Caused by: ".to_string(),
+			at: "at".to_string(),
+			more: "... {} more".to_string(),
+			omitted: "... {} frames omitted ...".to_string(),
+			repeated: " (repeated {} times)".to_string(),
+			handled_by: "handled by".to_string(),
+			suppressed: "... suppressed {} similar exceptions".to_string(),
+			dependencies: "... {} dependency frames".to_string()
+		};
+	}
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const CYAN: &str = "\x1b[36m";
+
+/// Number of columns counted for a tab when fitting lines in a width
+const TAB_WIDTH: usize = 8;
+/// Minimum length of elided expressions, below which they would be meaningless
+const MIN_ELIDED: usize = 20;
+
+/// Renderer of stack traces
+#[derive(Clone, Debug)]
+pub struct Formatter {
+	style: Style,
+	color: bool,
+	max_frames: Option<usize>,
+	system_info: bool,
+	env_vars: Vec<String>,
+	width: Option<usize>,
+	path_prefixes: Vec<String>,
+	labels: Labels,
+	frame_renderer: Option<SharedFrameRenderer>,
+	app_paths: Vec<String>,
+	collapse_dependencies: bool
+}
+
+/// Same as `Formatter::new()`
+impl Default for Formatter {
+	fn default() -> Formatter {
+		return Formatter::new();
+	}
+}
+
+impl Formatter {
+	/// Create a formatter rendering traces like `Throwable::print_stack_trace` does
+	pub fn new() -> Formatter {
+		return Formatter{style: Style::Full, color: false, max_frames: None, system_info: false, env_vars: Vec::new(), width: None, path_prefixes: Vec::new(), labels: Labels::default(), frame_renderer: None, app_paths: Vec::new(), collapse_dependencies: false};
+	}
+	
+	/// Create a formatter with the settings of the global configuration, set with `config::set`.
+	/// It's the one used by `Throwable::print_stack_trace` and the other default renderings.
+	/// Colors are disabled like with `detect_color`, unless `force_color` is set
+	pub fn configured() -> Formatter {
+		let config = config::get();
+		let formatter = Formatter::from_config(&config);
+		if config.force_color {
+			enable_ansi_support();
+			return formatter;
+		}
+		return formatter.detect_color();
+	}
+	
+	/// Create a formatter with the settings of `config`
+	pub fn from_config(config: &FormatConfig) -> Formatter {
+		let mut formatter = Formatter::new().style(config.style).color(config.color).system_info(config.system_info).labels(config.labels.clone());
+		formatter.max_frames = config.max_frames;
+		for prefix in &config.path_strip {
+			formatter = formatter.strip_path_prefix(prefix);
+		}
+		for prefix in &config.app_paths {
+			formatter = formatter.app_path(prefix);
+		}
+		formatter = formatter.collapse_dependencies(config.collapse_dependencies);
+		return formatter;
+	}
+	
+	/// Create a formatter with the `Concise` style
+	pub fn concise() -> Formatter {
+		return Formatter::new().style(Style::Concise);
+	}
+	
+	/// Create a formatter with the `Verbose` style, and the system section enabled, for debug
+	/// logs and bug reports
+	pub fn verbose() -> Formatter {
+		return Formatter::new().style(Style::Verbose).system_info(true);
+	}
+	
+	/// Set the layout of the rendered traces
+	pub fn style(mut self, style: Style) -> Formatter {
+		self.style = style;
+		return self;
+	}
+	
+	/// Enable or disable ANSI colors
+	pub fn color(mut self, color: bool) -> Formatter {
+		self.color = color;
+		return self;
+	}
+	
+	/// Render at most `max` stack trace entries per `Throwable`. When exceeded, the outermost and
+	/// innermost entries are rendered, and the ones in the middle are elided
+	pub fn max_frames(mut self, max: usize) -> Formatter {
+		self.max_frames = Some(max);
+		return self;
+	}
+	
+	/// Fit stack trace entries in `width` columns, by collapsing whitespaces in expressions and
+	/// eliding the middle of long expressions and the leading directories of long paths
+	pub fn width(mut self, width: usize) -> Formatter {
+		self.width = Some(width);
+		return self;
+	}
+	
+	/// Fit stack trace entries in the width of the terminal, as given by the `COLUMNS`
+	/// environment variable. Nothing is changed if it's not set
+	pub fn detect_width(mut self) -> Formatter {
+		if let Some(width) = env::var("COLUMNS").ok().and_then(|c| c.trim().parse().ok()) {
+			self.width = Some(width);
+		}
+		return self;
+	}
+	
+	/// Set the fixed texts of rendered traces
+	pub fn labels(mut self, labels: Labels) -> Formatter {
+		self.labels = labels;
+		return self;
+	}
+	
+	/// Render the stack trace entries with `renderer`, instead of as `at expr [file:line]`. It's
+	/// used by all the styles except `Python` and the ones for machines
+	pub fn frame_renderer<R: FrameRenderer + 'static>(mut self, renderer: R) -> Formatter {
+		self.frame_renderer = Some(SharedFrameRenderer(Arc::new(renderer)));
+		return self;
+	}
+	
+	/// Mark the files starting with `prefix` as application code, like `src/`. Once set, the
+	/// expressions of the entries in the application code are in bold when colors are enabled,
+	/// and the other entries are dependency ones. Files are matched once stripped of the prefixes
+	/// given to `strip_path_prefix`
+	pub fn app_path(mut self, prefix: &str) -> Formatter {
+		self.app_paths.push(prefix.to_string());
+		return self;
+	}
+	
+	/// Replace consecutive stack trace entries outside of the application code by a single line
+	pub fn collapse_dependencies(mut self, enabled: bool) -> Formatter {
+		self.collapse_dependencies = enabled;
+		return self;
+	}
+	
+	/// Remove `prefix` from the start of the files of stack trace entries, like the directory of
+	/// the workspace or of the registry of dependencies, to shorten them
+	pub fn strip_path_prefix(mut self, prefix: &str) -> Formatter {
+		self.path_prefixes.push(prefix.to_string());
+		return self;
+	}
+	
+	/// Disable colors if stderr isn't a terminal, or if the `NO_COLOR` environment variable is
+	/// set, or `CLICOLOR` is `0`. Setting `CLICOLOR_FORCE` to anything but `0` enables them. On
+	/// Windows, the console is switched to interpreting ANSI sequences, and colors are disabled
+	/// for legacy consoles not supporting it
+	pub fn detect_color(mut self) -> Formatter {
+		if env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0") {
+			enable_ansi_support();
+			self.color = true;
+			return self;
+		}
+		let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+		if no_color || env::var_os("CLICOLOR").is_some_and(|v| v == "0") || !io::stderr().is_terminal() || (self.color && !enable_ansi_support()) {
+			self.color = false;
+		}
+		return self;
+	}
+	
+	/// Append a section with the operating system, the architecture and the hostname
+	pub fn system_info(mut self, enabled: bool) -> Formatter {
+		self.system_info = enabled;
+		return self;
+	}
+	
+	/// Append a section with the values of the given environment variables. Only the variables
+	/// listed here are rendered, to avoid leaking secrets
+	pub fn env_vars(mut self, names: &[&str]) -> Formatter {
+		self.env_vars = names.iter().map(|n| n.to_string()).collect();
+		return self;
+	}
+	
+	/// Write the stack trace of `th` and of its causes to `out`
+	pub fn write<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		match self.style {
+			Style::Full => self.write_full(th, out)?,
+			Style::Verbose => {
+				let thread = thread::current();
+				writeln!(out, "Thread: {} ({:?})", thread.name().unwrap_or("<unnamed>"), thread.id())?;
+				writeln!(out, "Time: {}", timestamp(SystemTime::now()))?;
+				self.write_full(th, out)?;
+			},
+			Style::Python => {
+				writeln!(out, "Traceback (most recent call last):")?;
+				self.write_python(th, out)?;
+			},
+			Style::Flat => self.write_flat(th, out)?,
+			// Environment and build sections are left out too
+			Style::Concise => return self.write_concise(th, out),
+			Style::KeyValue => return self.write_key_value(th, out),
+			Style::Logfmt => return self.write_logfmt(th, out),
+			Style::Markdown => return self.write_markdown(th, "", out),
+			#[cfg(feature = "json")]
+			Style::Json => return writeln!(out, "{}", json::to_json(th))
+		}
+		self.write_environment(out)?;
+		if let Some(info) = build::build_info() {
+			writeln!(out, "Build: {}", info)?;
+		}
+		return Ok(());
+	}
+	
+	/// Render the stack trace of `th` and of its causes into a `String`
+	pub fn render<T: Throwable+?Sized>(&self, th: &T) -> String {
+		let mut out = Vec::new();
+		let _ = self.write(th, &mut out);
+		return String::from_utf8_lossy(&out).into_owned();
+	}
+	
+	fn write_full<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		self.write_message(th, out)?;
+		for (key, value) in th.get_context() {
+			writeln!(out, "\twith {} = {}", key, value)?;
+		}
+		let frames = self.frames_of(th);
+		let frames: Vec<&StackEntry> = frames.iter().collect();
+		self.write_frames(&frames, &self.gaps_of(th), "\t", out, &|f, out| {
+			if self.style == Style::Verbose {
+				return self.write_verbose_frame(f, out);
+			}
+			return self.write_full_frame(f, "\t", out);
+		})?;
+		if self.style == Style::Verbose {
+			if let Some(f) = th.handled_at().map(|f| self.resolve(f)) {
+				let file = absolute_path(&f.file);
+				if self.color {
+					writeln!(out, "\t{}{}{} {} {}[{}:{}]{}", DIM, self.labels.handled_by, RESET, f.expr, CYAN, file, f.line, RESET)?;
+				} else {
+					writeln!(out, "\t{} {} [{}:{}]", self.labels.handled_by, f.expr, file, f.line)?;
+				}
+			}
+		}
+		if let Some(cause) = th.get_cause() {
+			write!(out, "{}", self.labels.caused_by)?;
+			self.write_full(cause, out)?;
+		}
+		return Ok(());
+	}
+	
+	fn write_python<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		if let Some(cause) = th.get_cause() {
+			self.write_python(cause, out)?;
+			writeln!(out, "\nThe above exception was the direct cause of the following exception:\n")?;
+			writeln!(out, "Traceback (most recent call last):")?;
+		}
+		let frames = self.frames_of(th);
+		let frames: Vec<&StackEntry> = frames.iter().collect();
+		self.write_frames(&frames, &self.gaps_of(th), "  ", out, &|f, out| {
+			let (file, expr) = match self.width {
+				// `  File "", line ` around the file and the line, 4 spaces before the expression
+				Some(width) => {
+					let file = elide_path(&f.file, width.saturating_sub(17 + f.line.to_string().len()));
+					(file, elide_middle(&collapse_whitespaces(&f.expr), width.saturating_sub(4)))
+				},
+				None => (f.file.to_string(), f.expr.to_string())
+			};
+			if self.color {
+				writeln!(out, "  File {}\"{}\"{}, line {}{}{}", CYAN, file, RESET, CYAN, f.line, RESET)?;
+			} else {
+				writeln!(out, "  File \"{}\", line {}", file, f.line)?;
+			}
+			writeln!(out, "    {}", expr)?;
+			if f.repeat > 1 {
+				writeln!(out, "  [Previous line repeated {} more times]", f.repeat - 1)?;
+			}
+			return Ok(());
+		})?;
+		self.write_message(th, out)?;
+		for (key, value) in th.get_context() {
+			writeln!(out, "  {} = {}", key, value)?;
+		}
+		return Ok(());
+	}
+	
+	fn write_flat<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		self.write_message(th, out)?;
+		let mut levels: Vec<&Throwable> = th.causes().collect();
+		levels.reverse();
+		// The innermost entry of the root cause was recorded first
+		let mut resolved: Vec<StackEntry> = Vec::new();
+		let mut owners: Vec<usize> = Vec::new();
+		let mut gaps = Vec::new();
+		for (i, level) in levels.iter().enumerate() {
+			self.push_flat_level(*level, &mut resolved, &mut gaps);
+			owners.resize(resolved.len(), levels.len() - i);
+		}
+		self.push_flat_level(th, &mut resolved, &mut gaps);
+		owners.resize(resolved.len(), 0);
+		let frames: Vec<&StackEntry> = resolved.iter().collect();
+		writeln!(out, "Trace, from the first recorded entry:")?;
+		// The entries are written in order, so each search resumes after the previous entry
+		let next = Cell::new(0);
+		self.write_frames(&frames, &gaps, "\t", out, &|f, out| {
+			let start = next.get();
+			let owner = match frames[start..].iter().position(|&e| ptr::eq(e, f)) {
+				Some(i) => {
+					next.set(start + i + 1);
+					owners[start + i]
+				},
+				None => 0
+			};
+			let prefix = format!("[{}] ", owner);
+			if let Some(ref renderer) = self.frame_renderer {
+				return write_rendered_frame(&*renderer.0, f, &format!("\t{}", prefix), out);
+			}
+			let repeat = self.repeat(f);
+			let (expr, file) = self.fit(f, TAB_WIDTH + self.entry_overhead() + prefix.len() + f.line.to_string().len() + repeat.len());
+			let expr = self.highlight(f, expr);
+			if self.color {
+				return writeln!(out, "\t{}{}{}{} {} {}[{}:{}]{}{}", DIM, prefix, self.labels.at, RESET, expr, CYAN, file, f.line, RESET, repeat);
+			}
+			return writeln!(out, "\t{}{} {} [{}:{}]{}", prefix, self.labels.at, expr, file, f.line, repeat);
+		})?;
+		writeln!(out, "Exceptions:")?;
+		let legend = |i: usize, message: &str, context: &[(String, String)], out: &mut Write| -> io::Result<()> {
+			writeln!(out, "\t[{}] {}", i, message)?;
+			for (key, value) in context {
+				writeln!(out, "\t\twith {} = {}", key, value)?;
+			}
+			return Ok(());
+		};
+		legend(0, th.get_message(), th.get_context(), out)?;
+		for (i, cause) in th.causes().enumerate() {
+			legend(i + 1, cause.get_message(), cause.get_context(), out)?;
+		}
+		return Ok(());
+	}
+	
+	fn write_full_frame(&self, f: &StackEntry, indent: &str, out: &mut Write) -> io::Result<()> {
+		if let Some(ref renderer) = self.frame_renderer {
+			return write_rendered_frame(&*renderer.0, f, indent, out);
+		}
+		let repeat = self.repeat(f);
+		// The indentation, "at ", " [", ":" and "]" around the expression, the file and the line
+		let indent_width: usize = indent.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum();
+		let overhead = indent_width + self.entry_overhead() + f.line.to_string().len() + repeat.len();
+		let (expr, file) = self.fit(f, overhead);
+		let expr = self.highlight(f, expr);
+		if !self.color {
+			return writeln!(out, "{}{} {} [{}:{}]{}", indent, self.labels.at, expr, file, f.line, repeat);
+		}
+		return writeln!(out, "{}{}{}{} {} {}[{}:{}]{}{}", indent, DIM, self.labels.at, RESET, expr, CYAN, file, f.line, RESET, repeat);
+	}
+	
+	fn write_verbose_frame(&self, f: &StackEntry, out: &mut Write) -> io::Result<()> {
+		if let Some(ref renderer) = self.frame_renderer {
+			return write_rendered_frame(&*renderer.0, &self.strip(f.clone()), "\t", out);
+		}
+		let file = absolute_path(&f.file);
+		let repeat = self.repeat(f);
+		let expr = self.highlight(f, collapse_whitespaces(&f.expr));
+		if self.color {
+			return writeln!(out, "\t{}{}{} {} {}[{}:{}]{} in {}{}", DIM, self.labels.at, RESET, expr, CYAN, file, f.line, RESET, module_of(self.stripped_file(f)), repeat);
+		}
+		return writeln!(out, "\t{} {} [{}:{}] in {}{}", self.labels.at, expr, file, f.line, module_of(self.stripped_file(f)), repeat);
+	}
+	
+	fn write_concise<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		self.write_message(th, out)?;
+		if let Some(f) = th.frames().rev().find(|f| !f.is_boundary() && filter::rendered(f)).map(|f| self.resolve(f)) {
+			if let Some(ref renderer) = self.frame_renderer {
+				write_rendered_frame(&*renderer.0, &f, "\t", out)?;
+			} else {
+				let (expr, file) = self.fit(&f, TAB_WIDTH + self.entry_overhead() + f.line.to_string().len());
+				writeln!(out, "\t{} {} [{}:{}]", self.labels.at, expr, file, f.line)?;
+			}
+		}
+		if let Some(root) = th.causes().last() {
+			write!(out, "{}", self.labels.caused_by)?;
+			self.write_message(root, out)?;
+		}
+		return Ok(());
+	}
+	
+	fn write_key_value<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		write!(out, "ts={} level=error msg={}", timestamp(SystemTime::now()), quote_value(th.get_message()))?;
+		if !th.kind().is_empty() {
+			write!(out, " kind={}", quote_value(th.kind()))?;
+		}
+		if let Some(f) = th.frames().rev().find(|f| !f.is_boundary() && filter::rendered(f)).map(|f| self.resolve(f)) {
+			write!(out, " top_frame={}", quote_value(&format!("{}:{}", f.file, f.line)))?;
+		}
+		if let Some(root) = th.causes().last() {
+			write!(out, " cause={}", quote_value(root.get_message()))?;
+		}
+		return writeln!(out);
+	}
+	
+	fn write_logfmt<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		write!(out, "ts={} level=error", timestamp(SystemTime::now()))?;
+		self.write_logfmt_level("", th, out)?;
+		for (i, cause) in th.causes().enumerate() {
+			self.write_logfmt_level(&format!("cause.{}.", i + 1), cause, out)?;
+		}
+		return writeln!(out);
+	}
+	
+	/// Write `th` as Markdown, with `quote` before each line. The message is preceded by
+	/// `Caused by: ` for causes
+	fn write_markdown<T: Throwable+?Sized>(&self, th: &T, quote: &str, out: &mut Write) -> io::Result<()> {
+		let plain = Formatter{color: false, ..self.clone()};
+		let label = if quote.is_empty() { "" } else { self.labels.caused_by.as_str() };
+		writeln!(out, "{}{}**{}**", quote, label, escape_markdown(th.get_message()))?;
+		if !th.get_context().is_empty() {
+			writeln!(out, "{}", quote.trim_end())?;
+			for (key, value) in th.get_context() {
+				writeln!(out, "{}- {} = `{}`", quote, escape_markdown(key), value.replace('`', "'"))?;
+			}
+		}
+		let frames = self.frames_of(th);
+		if !frames.is_empty() {
+			let frames: Vec<&StackEntry> = frames.iter().collect();
+			writeln!(out, "{}\n{}```", quote.trim_end(), quote)?;
+			plain.write_frames(&frames, &self.gaps_of(th), quote, out, &|f, out| plain.write_full_frame(f, quote, out))?;
+			writeln!(out, "{}```", quote)?;
+		}
+		if let Some(cause) = th.get_cause() {
+			writeln!(out, "{}", quote.trim_end())?;
+			self.write_markdown(cause, &format!("{}> ", quote), out)?;
+		}
+		return Ok(());
+	}
+	
+	/// Write the logfmt pairs of `th` alone, with keys prefixed by `prefix`
+	fn write_logfmt_level<T: Throwable+?Sized>(&self, prefix: &str, th: &T, out: &mut Write) -> io::Result<()> {
+		write!(out, " {}msg={}", prefix, quote_value(th.get_message()))?;
+		if !th.kind().is_empty() {
+			write!(out, " {}kind={}", prefix, quote_value(th.kind()))?;
+		}
+		for (key, value) in th.get_context() {
+			write!(out, " {}context.{}={}", prefix, logfmt_key(key), quote_value(value))?;
+		}
+		for (i, f) in th.frames().filter(|f| !f.is_boundary() && filter::rendered(f)).map(|f| self.resolve(f)).enumerate() {
+			let frame = format!("{}:{} {}", f.file, f.line, collapse_whitespaces(&f.expr));
+			write!(out, " {}frame.{}={}", prefix, i, quote_value(&frame))?;
+		}
+		return Ok(());
+	}
+	
+	/// Get the number of columns taken by the label before the expression of a stack trace entry,
+	/// and the brackets around its file and line
+	fn entry_overhead(&self) -> usize {
+		return self.labels.at.chars().count() + 5;
+	}
+	
+	/// Get the text appended to stack trace entries repeated several times
+	fn repeat(&self, f: &StackEntry) -> String {
+		if f.repeat > 1 {
+			return fill(&self.labels.repeated, f.repeat);
+		}
+		return String::new();
+	}
+	
+	/// Get the stack trace entries of `th` kept by the render filters of the `filter` module,
+	/// resolved with `resolve`
+	fn frames_of<T: Throwable+?Sized>(&self, th: &T) -> Vec<StackEntry> {
+		return th.frames().filter(|f| filter::rendered(f)).map(|f| self.resolve(f)).collect();
+	}
+	
+	/// Get the entries dropped by `th`, with the position of the middle ones among the entries
+	/// kept by the render filters
+	fn dropped_of<T: Throwable+?Sized>(&self, th: &T) -> DroppedFrames {
+		let mut dropped = th.dropped_frames();
+		dropped.middle_at = th.frames().take(dropped.middle_at).filter(|f| filter::rendered(f)).count();
+		return dropped;
+	}
+	
+	/// Get where the entries dropped by `th` are missing among its entries kept by the render
+	/// filters, as gaps for `write_frames`
+	fn gaps_of<T: Throwable+?Sized>(&self, th: &T) -> Vec<(usize, usize)> {
+		let dropped = self.dropped_of(th);
+		return vec![(0, dropped.outermost), (dropped.middle_at, dropped.middle)];
+	}
+	
+	/// Append the entries of `th` kept by the render filters to `frames`, from the innermost one,
+	/// and where its dropped entries are missing to `gaps`, for `Style::Flat`
+	fn push_flat_level<T: Throwable+?Sized>(&self, th: &T, frames: &mut Vec<StackEntry>, gaps: &mut Vec<(usize, usize)>) {
+		let start = frames.len();
+		frames.extend(th.frames().rev().filter(|f| filter::rendered(f)).map(|f| self.resolve(f)));
+		let len = frames.len() - start;
+		// Reversed, the middle entries are before the `len - middle_at` first kept entries, and
+		// the outermost ones after all of them
+		let dropped = self.dropped_of(th);
+		gaps.push((start + len - cmp::min(dropped.middle_at, len), dropped.middle));
+		gaps.push((start + len, dropped.outermost));
+	}
+	
+	/// Get `f` at its original location, if it's covered by a source map, and with the first
+	/// matching prefix removed from its file. The prefix is kept with the `Verbose` style, which
+	/// prints absolute paths, and `stripped_file` removes it where needed
+	fn resolve(&self, f: &StackEntry) -> StackEntry {
+		let f = source_map::resolve(f);
+		if self.style == Style::Verbose {
+			return f;
+		}
+		return self.strip(f);
+	}
+	
+	/// Get `f` with the first matching prefix removed from its file
+	fn strip(&self, mut f: StackEntry) -> StackEntry {
+		if let Some(prefix) = self.path_prefixes.iter().find(|p| f.file.starts_with(p.as_str())) {
+			f.file = match f.file {
+				Cow::Borrowed(file) => Cow::Borrowed(&file[prefix.len()..]),
+				Cow::Owned(ref file) => Cow::Owned(file[prefix.len()..].to_string())
+			};
+		}
+		return f;
+	}
+	
+	/// Get the file of the resolved entry `f` without its prefix, which is still there with the
+	/// `Verbose` style
+	fn stripped_file<'a>(&self, f: &'a StackEntry) -> &'a str {
+		if self.style == Style::Verbose {
+			if let Some(prefix) = self.path_prefixes.iter().find(|p| f.file.starts_with(p.as_str())) {
+				return &f.file[prefix.len()..];
+			}
+		}
+		return &f.file;
+	}
+	
+	/// Get the expression and the file of `f`, shortened to fit in the configured width along
+	/// with `overhead` other characters
+	fn fit(&self, f: &StackEntry, overhead: usize) -> (String, String) {
+		let expr = collapse_whitespaces(&f.expr);
+		let width = match self.width {
+			Some(width) => width,
+			None => return (expr, f.file.to_string())
+		};
+		let available = width.saturating_sub(overhead);
+		if expr.chars().count() + f.file.chars().count() <= available {
+			return (expr, f.file.to_string());
+		}
+		// Paths are shortened first, keeping up to a third of the space for them
+		let file = elide_path(&f.file, cmp::max(available / 3, available.saturating_sub(expr.chars().count())));
+		let expr = elide_middle(&expr, available.saturating_sub(file.chars().count()));
+		return (expr, file);
+	}
+	
+	fn write_message<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		if self.color {
+			return writeln!(out, "{}{}{}", BOLD_RED, th.get_message(), RESET);
+		}
+		return writeln!(out, "{}", th.get_message());
+	}
+	
+	fn write_environment(&self, out: &mut Write) -> io::Result<()> {
+		if !self.system_info && self.env_vars.is_empty() {
+			return Ok(());
+		}
+		writeln!(out, "Environment:")?;
+		if self.system_info {
+			writeln!(out, "\tos: {} ({})", env::consts::OS, env::consts::FAMILY)?;
+			writeln!(out, "\tarch: {}", env::consts::ARCH)?;
+			writeln!(out, "\thostname: {}", hostname().unwrap_or_else(|| "<unknown>".to_string()))?;
+		}
+		for name in &self.env_vars {
+			match env::var_os(name) {
+				Some(value) => writeln!(out, "\t{}={}", name, value.to_string_lossy())?,
+				None => writeln!(out, "\t{} is not set", name)?
+			}
+		}
+		return Ok(());
+	}
+	
+	/// Write `frames`, with a line where entries are missing for each of the `gaps`, given in
+	/// order as the index of the entry the missing ones were before and their number. The
+	/// entries exceeding `max_frames` are elided in the middle
+	fn write_frames(&self, frames: &[&StackEntry], gaps: &[(usize, usize)], indent: &str, out: &mut Write, write_frame: &Fn(&StackEntry, &mut Write) -> io::Result<()>) -> io::Result<()> {
+		let max = self.max_frames.unwrap_or(frames.len());
+		let (head, tail) = if frames.len() <= max { (frames.len(), frames.len()) } else { (max / 2, frames.len() - (max - max / 2)) };
+		let mut start = 0;
+		for &(at, count) in gaps.iter().chain(Some(&(frames.len(), 0))) {
+			let at = cmp::min(cmp::max(at, start), frames.len());
+			// The entries from `head` to `tail` are replaced by a line, where the first run
+			// reaching them starts eliding them
+			self.write_frame_run(&frames[start..cmp::max(start, cmp::min(at, head))], indent, out, write_frame)?;
+			if start <= head && head < at && head < tail {
+				writeln!(out, "{}{}", indent, fill(&self.labels.more, tail - head))?;
+			}
+			self.write_frame_run(&frames[cmp::min(cmp::max(start, tail), at)..at], indent, out, write_frame)?;
+			if count > 0 {
+				self.write_omitted(count, indent, out)?;
+			}
+			start = at;
+		}
+		return Ok(());
+	}
+	
+	/// Write the line replacing `count` entries dropped by a `Throwable`
+	fn write_omitted(&self, count: usize, indent: &str, out: &mut Write) -> io::Result<()> {
+		let line = fill(&self.labels.omitted, count);
+		if self.color {
+			return writeln!(out, "{}{}{}{}", indent, DIM, line, RESET);
+		}
+		return writeln!(out, "{}{}", indent, line);
+	}
+	
+	/// Write `frames` with `write_frame`, replacing consecutive dependency entries by a single
+	/// line if `collapse_dependencies` is enabled
+	fn write_frame_run(&self, frames: &[&StackEntry], indent: &str, out: &mut Write, write_frame: &Fn(&StackEntry, &mut Write) -> io::Result<()>) -> io::Result<()> {
+		let mut i = 0;
+		while i < frames.len() {
+			let dependencies = frames[i..].iter().take_while(|f| !f.is_boundary() && !self.is_app_frame(f)).count();
+			if self.collapse_dependencies && dependencies > 1 {
+				let line = fill(&self.labels.dependencies, dependencies);
+				if self.color {
+					writeln!(out, "{}{}{}{}", indent, DIM, line, RESET)?;
+				} else {
+					writeln!(out, "{}{}", indent, line)?;
+				}
+				i += dependencies;
+				continue;
+			}
+			self.write_frame(frames[i], indent, out, write_frame)?;
+			i += 1;
+		}
+		return Ok(());
+	}
+	
+	/// Check if `f` is in the application code, which is always the case if no application path
+	/// is set
+	fn is_app_frame(&self, f: &StackEntry) -> bool {
+		let file = self.stripped_file(f);
+		return self.app_paths.is_empty() || self.app_paths.iter().any(|p| file.starts_with(p.as_str()));
+	}
+	
+	/// Get `expr` in bold if `f` is in the application code, colors are enabled and application
+	/// paths are set
+	fn highlight(&self, f: &StackEntry, expr: String) -> String {
+		if self.color && !self.app_paths.is_empty() && self.is_app_frame(f) {
+			return format!("{}{}{}", BOLD, expr, RESET);
+		}
+		return expr;
+	}
+	
+	/// Write `f` with `write_frame`, or as a separator line if it's a boundary
+	fn write_frame(&self, f: &StackEntry, indent: &str, out: &mut Write, write_frame: &Fn(&StackEntry, &mut Write) -> io::Result<()>) -> io::Result<()> {
+		if !f.is_boundary() {
+			return write_frame(f, out);
+		}
+		if self.color {
+			return writeln!(out, "{}{}── {} ──{}", indent, DIM, f.expr, RESET);
+		}
+		return writeln!(out, "{}── {} ──", indent, f.expr);
+	}
+}
+
+/// Get `file` joined to the current directory if it's relative
+fn absolute_path(file: &str) -> String {
+	let path = Path::new(file);
+	return match env::current_dir() {
+		Ok(ref dir) if path.is_relative() => dir.join(path).display().to_string(),
+		_ => file.to_string()
+	};
+}
+
+/// Enable the interpretation of ANSI sequences by the console of stderr, which Windows consoles
+/// don't do by default. Return whether they are interpreted
+#[cfg(windows)]
+fn enable_ansi_support() -> bool {
+	type Handle = *mut u8;
+	const STD_ERROR_HANDLE: u32 = -12i32 as u32;
+	const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+	#[link(name = "kernel32")]
+	extern "system" {
+		fn GetStdHandle(std_handle: u32) -> Handle;
+		fn GetConsoleMode(console: Handle, mode: *mut u32) -> i32;
+		fn SetConsoleMode(console: Handle, mode: u32) -> i32;
+	}
+	unsafe {
+		let handle = GetStdHandle(STD_ERROR_HANDLE);
+		let mut mode = 0;
+		// A null or invalid handle has no console mode either
+		if GetConsoleMode(handle, &mut mode) == 0 {
+			return false;
+		}
+		return mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0;
+	}
+}
+
+/// Terminals of other platforms interpret ANSI sequences
+#[cfg(not(windows))]
+fn enable_ansi_support() -> bool {
+	return true;
+}
+
+/// Guess the module of a source file, from its path relative to the `src` directory
+fn module_of(file: &str) -> String {
+	let file = file.replace('\\', "/");
+	let relative = match file.rfind("src/") {
+		Some(i) => &file[i + 4..],
+		None => &file[..]
+	};
+	let relative = relative.trim_end_matches(".rs").trim_end_matches("/mod");
+	let mut module = "crate".to_string();
+	for part in relative.split('/') {
+		if part != "lib" && part != "main" && !part.is_empty() {
+			module.push_str("::");
+			module.push_str(part);
+		}
+	}
+	return module;
+}
+
+/// Quote `value` if it's empty or has spaces, quotes or equal signs, escaping the quotes,
+/// backslashes and line breaks, so that it's a single value of a `key=value` line
+fn quote_value(value: &str) -> String {
+	if !value.is_empty() && !value.contains(|c: char| c.is_whitespace() || c.is_control() || c == '"' || c == '=' || c == '\\') {
+		return value.to_string();
+	}
+	let mut quoted = String::from("\"");
+	for c in value.chars() {
+		match c {
+			'"' => quoted.push_str("\\\""),
+			'\\' => quoted.push_str("\\\\"),
+			'\n' => quoted.push_str("\\n"),
+			'\r' => quoted.push_str("\\r"),
+			'\t' => quoted.push_str("\\t"),
+			c if c.is_control() => quoted.push_str(&format!("\\u{{{:04x}}}", c as u32)),
+			c => quoted.push(c)
+		}
+	}
+	quoted.push('"');
+	return quoted;
+}
+
+/// Escape the characters of `text` having a meaning in Markdown
+fn escape_markdown(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		if "\\`*_{}[]<>()#+-.!|~".contains(c) {
+			escaped.push('\\');
+		}
+		escaped.push(c);
+	}
+	return escaped;
+}
+
+/// Write `f` with `renderer`, after `indent`
+fn write_rendered_frame(renderer: &FrameRenderer, f: &StackEntry, indent: &str, out: &mut Write) -> io::Result<()> {
+	write!(out, "{}", indent)?;
+	renderer.render(f, out)?;
+	return writeln!(out);
+}
+
+/// Replace the `{}` of `template` by `n`
+fn fill<N: fmt::Display>(template: &str, n: N) -> String {
+	return template.replacen("{}", &n.to_string(), 1);
+}
+
+/// Replace the characters of `key` not allowed in a logfmt key by underscores
+fn logfmt_key(key: &str) -> String {
+	return key.chars().map(|c| if c.is_whitespace() || c.is_control() || c == '"' || c == '=' { '_' } else { c }).collect();
+}
+
+/// Format `time` as an RFC 3339 UTC date and time, like `2020-01-31T12:00:00Z`
+fn timestamp(time: SystemTime) -> String {
+	let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+	let (days, rest) = (secs / 86400, secs % 86400);
+	// Conversion of a number of days since the epoch to a civil date, from
+	// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+	let z = days as i64 + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+	return format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rest / 3600, rest % 3600 / 60, rest % 60);
+}
+
+/// Replace each sequence of whitespaces, including new lines, with a single space
+fn collapse_whitespaces(text: &str) -> String {
+	return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+}
+
+/// Shorten `text` to at most `max` characters, or `MIN_ELIDED` if greater, by replacing its
+/// middle with `...`
+fn elide_middle(text: &str, max: usize) -> String {
+	let max = cmp::max(max, MIN_ELIDED);
+	let chars: Vec<char> = text.chars().collect();
+	if chars.len() <= max {
+		return text.to_string();
+	}
+	let head = (max - 3) / 2;
+	let tail = max - 3 - head;
+	let mut elided: String = chars[..head].iter().collect();
+	elided.push_str("...");
+	elided.extend(&chars[chars.len() - tail..]);
+	return elided;
+}
+
+/// Shorten `path` to at most `max` characters by replacing its leading directories with `...`.
+/// The file name is always kept
+fn elide_path(path: &str, max: usize) -> String {
+	if path.chars().count() <= max {
+		return path.to_string();
+	}
+	let mut rest = path;
+	while let Some(i) = rest.find(['/', '\\']) {
+		rest = &rest[i + 1..];
+		if rest.chars().count() + 4 <= max {
+			break;
+		}
+	}
+	if rest.len() == path.len() {
+		return path.to_string();
+	}
+	return format!("...{}{}", &path[path.len() - rest.len() - 1..path.len() - rest.len()], rest);
+}
+
+/// Get the name of the host from the environment, or from the kernel on Linux
+fn hostname() -> Option<String> {
+	for var in &["HOSTNAME", "COMPUTERNAME"] {
+		if let Ok(name) = env::var(var) {
+			return Some(name);
+		}
+	}
+	for path in &["/proc/sys/kernel/hostname", "/etc/hostname"] {
+		if let Ok(name) = fs::read_to_string(path) {
+			return Some(name.trim().to_string());
+		}
+	}
+	return None;
+}
+
+/// Hook suggesting how to fix a failure, given to `ExceptionReport::suggestion`
+pub type Suggestion = Box<Fn(&Throwable) -> Option<String> + Send + Sync>;
+
+/// Renderer of a top level error screen made of sections: the message, the stack trace, the
+/// causes with their own stack traces, the context values of the whole chain, the environment
+/// and the suggestions. Empty sections are left out. The entries are rendered by a `Formatter`,
+/// whose colors, width, maximum number of entries and environment section are used
+/// 
+/// ```
+/// # use std::io;
+/// # use exceptions::prelude::*;
+/// # use exceptions::format::ExceptionReport;
+/// # let e = Exception::from_error(io::Error::from(io::ErrorKind::PermissionDenied));
+/// let report = ExceptionReport::new().color(true).suggestion(|th| {
+///     if th.is_instance_of("io.permission_denied") { Some("Run the command with sudo".to_string()) } else { None }
+/// });
+/// eprint!("{}", report.render(&e));
+/// # assert!(report.render(&e).contains("Run the command with sudo"));
+/// ```
+pub struct ExceptionReport {
+	formatter: Formatter,
+	suggestions: Vec<Suggestion>
+}
+
+/// Same as `ExceptionReport::new()`
+impl Default for ExceptionReport {
+	fn default() -> ExceptionReport {
+		return ExceptionReport::new();
+	}
+}
+
+impl ExceptionReport {
+	/// Create a report without colors nor suggestions
+	pub fn new() -> ExceptionReport {
+		return ExceptionReport{formatter: Formatter::new(), suggestions: Vec::new()};
+	}
+	
+	/// Render the stack trace entries and the environment section with `formatter`. Its style
+	/// is ignored
+	pub fn formatter(mut self, formatter: Formatter) -> ExceptionReport {
+		self.formatter = formatter;
+		return self;
+	}
+	
+	/// Enable or disable ANSI colors
+	pub fn color(mut self, color: bool) -> ExceptionReport {
+		self.formatter = self.formatter.color(color);
+		return self;
+	}
+	
+	/// Add a hook giving a suggestion for the rendered `Throwable`, if it has one for it. Hooks
+	/// are called with each `Throwable` of the cause chain, in the order they were added
+	pub fn suggestion<F>(mut self, hook: F) -> ExceptionReport where F: Fn(&Throwable) -> Option<String> + Send + Sync + 'static {
+		self.suggestions.push(Box::new(hook));
+		return self;
+	}
+	
+	/// Write the report of `th` to `out`
+	pub fn write<T: Throwable>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		let levels: Vec<&Throwable> = Some(th as &Throwable).into_iter().chain(th.causes()).collect();
+		let f = &self.formatter;
+		self.write_heading("Error: ", out)?;
+		f.write_message(th, out)?;
+		
+		let frames = self.formatter.frames_of(th);
+		if !frames.is_empty() {
+			writeln!(out)?;
+			self.write_heading("Stack trace:\n", out)?;
+			let frames: Vec<&StackEntry> = frames.iter().collect();
+			f.write_frames(&frames, &f.gaps_of(th), "\t", out, &|e, out| f.write_full_frame(e, "\t", out))?;
+		}
+		
+		if levels.len() > 1 {
+			writeln!(out)?;
+			self.write_heading("Causes:\n", out)?;
+			for (i, cause) in levels[1..].iter().enumerate() {
+				write!(out, "\t{}: ", i)?;
+				f.write_message(*cause, out)?;
+				let frames = self.formatter.frames_of(*cause);
+				let frames: Vec<&StackEntry> = frames.iter().collect();
+				f.write_frames(&frames, &f.gaps_of(*cause), "\t\t", out, &|e, out| f.write_full_frame(e, "\t\t", out))?;
+			}
+		}
+		
+		if levels.iter().any(|l| !l.get_context().is_empty()) {
+			writeln!(out)?;
+			self.write_heading("Context:\n", out)?;
+			for (i, level) in levels.iter().enumerate() {
+				for (key, value) in level.get_context() {
+					if i == 0 {
+						writeln!(out, "\t{} = {}", key, value)?;
+					} else {
+						writeln!(out, "\t{} = {} (cause {})", key, value, i - 1)?;
+					}
+				}
+			}
+		}
+		
+		let mut environment = Vec::new();
+		f.write_environment(&mut environment)?;
+		if let Some(info) = build::build_info() {
+			writeln!(environment, "Build: {}", info)?;
+		}
+		if !environment.is_empty() {
+			writeln!(out)?;
+			out.write_all(&environment)?;
+		}
+		
+		let suggestions: Vec<String> = levels.iter().flat_map(|l| self.suggestions.iter().filter_map(move |s| s(*l))).collect();
+		if !suggestions.is_empty() {
+			writeln!(out)?;
+			self.write_heading("Suggestions:\n", out)?;
+			for suggestion in suggestions {
+				writeln!(out, "\t- {}", suggestion)?;
+			}
+		}
+		return Ok(());
+	}
+	
+	/// Render the report of `th` into a `String`
+	pub fn render<T: Throwable>(&self, th: &T) -> String {
+		let mut out = Vec::new();
+		let _ = self.write(th, &mut out);
+		return String::from_utf8_lossy(&out).into_owned();
+	}
+	
+	fn write_heading(&self, heading: &str, out: &mut Write) -> io::Result<()> {
+		if self.formatter.color {
+			return write!(out, "{}{}{}", BOLD, heading, RESET);
+		}
+		return write!(out, "{}", heading);
+	}
+}
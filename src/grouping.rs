@@ -0,0 +1,184 @@
+//! Grouping of similar exceptions, for aggregation and deduplication
+use std::collections::HashMap;
+use std::sync::Mutex;
+use Throwable;
+use format::Formatter;
+
+/// Number of innermost stack trace entries taken in a `GroupingKey`
+const KEY_FRAMES: usize = 3;
+
+/// Key identifying similar exceptions: they have the same kind, the same message once numbers
+/// and quoted strings are replaced by placeholders, and were thrown from the same place. It's
+/// made with `Throwable::grouping_key`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GroupingKey {
+	kind: String,
+	template: String,
+	frames: Vec<(String, u32, String)>
+}
+
+impl GroupingKey {
+	/// Get the key of `th`
+	pub fn of<T: Throwable+?Sized>(th: &T) -> GroupingKey {
+		return GroupingKey{
+			kind: th.kind().to_string(),
+			template: message_template(th.get_message()),
+			frames: th.frames().rev().take(KEY_FRAMES).map(|f| (f.file.to_string(), f.line, f.expr.to_string())).collect()
+		};
+	}
+	
+	/// Get the kind of the grouped exceptions
+	pub fn kind(&self) -> &str {
+		return &self.kind;
+	}
+	
+	/// Get the message of the grouped exceptions, where numbers are replaced by `<n>` and quoted
+	/// strings by `"<s>"`
+	pub fn template(&self) -> &str {
+		return &self.template;
+	}
+}
+
+/// Compute the fingerprint of `th`, as 16 hexadecimal digits. It's made from its kind, its
+/// message template and the file and expression of its innermost stack trace entry, leaving
+/// out the line, so that it's stable across releases. It's made with `Throwable::fingerprint`
+pub fn fingerprint<T: Throwable+?Sized>(th: &T) -> String {
+	// 64-bit FNV-1a, whose result doesn't depend on the platform nor on the Rust version
+	let mut hash: u64 = 0xcbf29ce484222325;
+	let template = message_template(th.get_message());
+	let (file, expr) = match th.frames().rev().find(|f| !f.is_boundary()) {
+		Some(f) => (&*f.file, &*f.expr),
+		None => ("", "")
+	};
+	for part in &[th.kind(), &template, file, expr] {
+		// Parts are terminated by a byte which isn't valid UTF-8, so that they can't run together
+		for byte in part.bytes().chain(Some(0xff)) {
+			hash ^= byte as u64;
+			hash = hash.wrapping_mul(0x100000001b3);
+		}
+	}
+	return format!("{:016x}", hash);
+}
+
+/// Similar exceptions collected by an `ExceptionAggregator`
+#[derive(Clone, Debug)]
+pub struct Group {
+	/// The key of the exceptions of the group
+	pub key: GroupingKey,
+	/// The number of exceptions in the group
+	pub count: u64,
+	/// The rendered stack trace of the first exception of the group
+	pub trace: String
+}
+
+/// Collector of exceptions, counting them by group of similar ones and keeping the trace of
+/// the first one of each group. It's meant for jobs encountering the same failures many times,
+/// to report each of them once at the end
+/// 
+/// ```
+/// # use exceptions::prelude::*;
+/// # use exceptions::grouping::ExceptionAggregator;
+/// # fn process(record: u32) -> Result<(), Exception> {
+/// #     return Err(Exception::new(format!("Invalid record {}", record)));
+/// # }
+/// # let records = vec![1, 2, 3];
+/// let aggregator = ExceptionAggregator::new();
+/// for record in records {
+///     if let Err(e) = process(record) {
+///         aggregator.add(&e);
+///     }
+/// }
+/// eprint!("{}", aggregator.report());
+/// # assert_eq!(aggregator.groups().len(), 1);
+/// ```
+pub struct ExceptionAggregator {
+	groups: Mutex<HashMap<GroupingKey, Group>>
+}
+
+/// Same as `ExceptionAggregator::new()`
+impl Default for ExceptionAggregator {
+	fn default() -> ExceptionAggregator {
+		return ExceptionAggregator::new();
+	}
+}
+
+impl ExceptionAggregator {
+	/// Create an aggregator without any exception
+	pub fn new() -> ExceptionAggregator {
+		return ExceptionAggregator{groups: Mutex::new(HashMap::new())};
+	}
+	
+	/// Count `th` in its group, keeping its trace if it's the first one of the group
+	pub fn add<T: Throwable+?Sized>(&self, th: &T) {
+		let key = th.grouping_key();
+		let mut groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+		groups.entry(key.clone()).or_insert_with(|| Group{key: key, count: 0, trace: Formatter::new().render(th)}).count += 1;
+	}
+	
+	/// Get the groups, from the one having the most exceptions to the one having the least
+	pub fn groups(&self) -> Vec<Group> {
+		let groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+		let mut groups: Vec<Group> = groups.values().cloned().collect();
+		groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+		return groups;
+	}
+	
+	/// Get the total number of exceptions added
+	pub fn count(&self) -> u64 {
+		let groups = self.groups.lock().unwrap_or_else(|e| e.into_inner());
+		return groups.values().map(|g| g.count).sum();
+	}
+	
+	/// Remove all the exceptions
+	pub fn clear(&self) {
+		self.groups.lock().unwrap_or_else(|e| e.into_inner()).clear();
+	}
+	
+	/// Render a summary of the groups, each with its number of exceptions and the trace of its
+	/// first one
+	pub fn report(&self) -> String {
+		let groups = self.groups();
+		let mut report = format!("{} exceptions in {} groups\n", self.count(), groups.len());
+		for group in groups {
+			report.push_str(&format!("\n{} times: {}\n", group.count, group.key.template()));
+			for line in group.trace.lines() {
+				report.push('\t');
+				report.push_str(line);
+				report.push('\n');
+			}
+		}
+		return report;
+	}
+}
+
+/// Replace numbers by `<n>` and the content of quoted strings by `<s>` in `message`. Digits
+/// and quotes following a letter, like in `utf8` or `can't`, are kept
+fn message_template(message: &str) -> String {
+	let mut template = String::with_capacity(message.len());
+	let mut chars = message.chars().peekable();
+	let mut previous = ' ';
+	while let Some(c) = chars.next() {
+		let in_word = previous.is_alphanumeric();
+		previous = c;
+		if in_word {
+			template.push(c);
+		} else if c.is_ascii_digit() {
+			while chars.peek().is_some_and(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '_') {
+				chars.next();
+			}
+			template.push_str("<n>");
+		} else if c == '"' || c == '\'' || c == '`' {
+			template.push(c);
+			template.push_str("<s>");
+			for next in chars.by_ref() {
+				if next == c {
+					template.push(c);
+					break;
+				}
+			}
+		} else {
+			template.push(c);
+		}
+	}
+	return template;
+}
@@ -0,0 +1,259 @@
+//! Rendering of stack traces as standalone HTML pages, to attach to CI failure artifacts or to
+//! incident reports. Causes are collapsible sections, the source lines around each stack trace
+//! entry are shown when its file is in one of the source roots given to the renderer, and a
+//! search field filters the entries
+//! 
+//! ```
+//! use exceptions::Exception;
+//! use exceptions::html::HtmlReport;
+//! 
+//! let e = Exception::new("Cannot load configuration".to_string());
+//! let page = HtmlReport::new().title("Nightly build").render(&e);
+//! assert!(page.starts_with("<!DOCTYPE html>"));
+//! ```
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use {Throwable, StackEntry, source_map};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+h1 { color: #b00; font-size: 1.4em; }
+details { border-left: 3px solid #ccc; margin: 1em 0; padding-left: 1em; }
+summary { cursor: pointer; font-weight: bold; }
+ul.frames { list-style: none; padding: 0; }
+li.frame { margin: 0.5em 0; }
+li.boundary { color: #888; margin: 0.5em 0; }
+pre.snippet { background: #f6f8fa; margin: 0.3em 0; padding: 0.5em; }
+pre.snippet .current { background: #fff3b0; display: inline-block; width: 100%; }
+.line-number { color: #999; user-select: none; }
+.kw { color: #a626a4; } .str { color: #50a14f; } .com { color: #a0a1a7; } .num { color: #986801; }
+table.context td { font-family: monospace; padding: 0 1em 0 0; }";
+
+const SCRIPT: &str = "document.getElementById('filter').addEventListener('input', function() {
+	var text = this.value.toLowerCase();
+	document.querySelectorAll('li.frame').forEach(function(frame) {
+		frame.style.display = frame.dataset.search.toLowerCase().indexOf(text) < 0 ? 'none' : '';
+	});
+});";
+
+const KEYWORDS: &[&str] = &[
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+	"fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+	"self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while"
+];
+
+/// Renderer of stack traces as HTML pages
+#[derive(Clone, Debug)]
+pub struct HtmlReport {
+	title: Option<String>,
+	snippet_lines: usize,
+	source_roots: Vec<PathBuf>
+}
+
+/// Same as `HtmlReport::new()`
+impl Default for HtmlReport {
+	fn default() -> HtmlReport {
+		return HtmlReport::new();
+	}
+}
+
+impl HtmlReport {
+	/// Create a renderer without source code snippets. Once source roots are added, 3 lines of
+	/// source code are shown around each stack trace entry whose file is in one of them
+	pub fn new() -> HtmlReport {
+		return HtmlReport{title: None, snippet_lines: 3, source_roots: Vec::new()};
+	}
+	
+	/// Set the title of the page, which is the message of the exception by default
+	pub fn title(mut self, title: &str) -> HtmlReport {
+		self.title = Some(title.to_string());
+		return self;
+	}
+	
+	/// Show `lines` lines of source code before and after each stack trace entry. Snippets are
+	/// disabled with 0
+	pub fn snippet_lines(mut self, lines: usize) -> HtmlReport {
+		self.snippet_lines = lines;
+		return self;
+	}
+	
+	/// Allow snippets of the files in the directory `root`, relative paths of stack trace
+	/// entries being relative to it. Files outside of the source roots are never read, even
+	/// through `..` components or symbolic links. Roots which don't exist are ignored
+	/// 
+	/// ```
+	/// # #[macro_use] extern crate exceptions;
+	/// # use exceptions::prelude::*;
+	/// # use exceptions::html::HtmlReport;
+	/// # fn fail() -> Result<(), Exception> { throw!("Cannot connect"); }
+	/// # let e = fail().unwrap_err();
+	/// let page = HtmlReport::new().source_root(env!("CARGO_MANIFEST_DIR")).render(&e);
+	/// # assert!(page.contains("Cannot connect"));
+	/// ```
+	pub fn source_root<P: AsRef<Path>>(mut self, root: P) -> HtmlReport {
+		if let Ok(root) = root.as_ref().canonicalize() {
+			self.source_roots.push(root);
+		}
+		return self;
+	}
+	
+	/// Write the page rendering `th` and its causes to `out`
+	pub fn write<T: Throwable+?Sized>(&self, th: &T, out: &mut Write) -> io::Result<()> {
+		let title = self.title.as_deref().unwrap_or(th.get_message());
+		writeln!(out, "<!DOCTYPE html>")?;
+		writeln!(out, "<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", escape(title))?;
+		writeln!(out, "<style>\n{}\n</style>\n</head>\n<body>", STYLE)?;
+		writeln!(out, "<h1>{}</h1>", escape(th.get_message()))?;
+		writeln!(out, "<input id=\"filter\" type=\"search\" placeholder=\"Filter stack trace entries\">")?;
+		let mut sources = HashMap::new();
+		self.write_level(th, out, &mut sources)?;
+		for cause in th.causes() {
+			writeln!(out, "<details open>\n<summary>Caused by: {}</summary>", escape(cause.get_message()))?;
+			self.write_level(cause, out, &mut sources)?;
+			writeln!(out, "</details>")?;
+		}
+		writeln!(out, "<script>\n{}\n</script>", SCRIPT)?;
+		return writeln!(out, "</body>\n</html>");
+	}
+	
+	/// Render the page of `th` into a `String`
+	pub fn render<T: Throwable+?Sized>(&self, th: &T) -> String {
+		let mut out = Vec::new();
+		let _ = self.write(th, &mut out);
+		return String::from_utf8_lossy(&out).into_owned();
+	}
+	
+	/// Write the context and the stack trace entries of `th` alone. Source files are read once,
+	/// and kept in `sources`
+	fn write_level<T: Throwable+?Sized>(&self, th: &T, out: &mut Write, sources: &mut HashMap<String, Option<Vec<String>>>) -> io::Result<()> {
+		if !th.get_context().is_empty() {
+			writeln!(out, "<table class=\"context\">")?;
+			for (key, value) in th.get_context() {
+				writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", escape(key), escape(value))?;
+			}
+			writeln!(out, "</table>")?;
+		}
+		writeln!(out, "<ul class=\"frames\">")?;
+		for f in th.frames().map(source_map::resolve) {
+			if f.is_boundary() {
+				writeln!(out, "<li class=\"boundary\">── {} ──</li>", escape(&f.expr))?;
+				continue;
+			}
+			let search = format!("{} {}", f.file, f.expr);
+			writeln!(out, "<li class=\"frame\" data-search=\"{}\">", escape(&search))?;
+			write!(out, "<code>at {} [{}:{}]</code>", escape(&f.expr), escape(&f.file), f.line)?;
+			if f.repeat > 1 {
+				write!(out, " (repeated {} times)", f.repeat)?;
+			}
+			writeln!(out)?;
+			if self.snippet_lines > 0 && !self.source_roots.is_empty() {
+				let lines = sources.entry(f.file.to_string()).or_insert_with(|| {
+					let source = self.source_path(&f.file).and_then(|p| fs::read_to_string(p).ok());
+					return source.map(|s| s.lines().map(|l| l.to_string()).collect());
+				});
+				if let Some(ref lines) = *lines {
+					self.write_snippet(&f, lines, out)?;
+				}
+			}
+			writeln!(out, "</li>")?;
+		}
+		return writeln!(out, "</ul>");
+	}
+	
+	/// Get the path of `file` in the first source root containing it, once `..` components and
+	/// symbolic links are resolved
+	fn source_path(&self, file: &str) -> Option<PathBuf> {
+		for root in &self.source_roots {
+			if let Ok(path) = root.join(file).canonicalize() {
+				if path.starts_with(root) {
+					return Some(path);
+				}
+			}
+		}
+		return None;
+	}
+	
+	fn write_snippet(&self, f: &StackEntry, lines: &[String], out: &mut Write) -> io::Result<()> {
+		let line = f.line as usize;
+		if line == 0 || line > lines.len() {
+			return Ok(());
+		}
+		let first = line.saturating_sub(self.snippet_lines).max(1);
+		let last = (line + self.snippet_lines).min(lines.len());
+		let width = last.to_string().len();
+		write!(out, "<pre class=\"snippet\">")?;
+		for n in first..last + 1 {
+			let number = format!("<span class=\"line-number\">{:>width$} </span>", n, width = width);
+			if n == line {
+				writeln!(out, "<span class=\"current\">{}{}</span>", number, highlight(&lines[n - 1]))?;
+			} else {
+				writeln!(out, "{}{}", number, highlight(&lines[n - 1]))?;
+			}
+		}
+		return writeln!(out, "</pre>");
+	}
+}
+
+/// Escape the characters of `text` having a meaning in HTML
+fn escape(text: &str) -> String {
+	let mut escaped = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&#39;"),
+			c => escaped.push(c)
+		}
+	}
+	return escaped;
+}
+
+/// Highlight a line of Rust code, wrapping keywords, string literals, numbers and comments in
+/// spans. A string literal spanning several lines is only highlighted on its first line
+fn highlight(line: &str) -> String {
+	let chars: Vec<char> = line.chars().collect();
+	let mut html = String::new();
+	let mut i = 0;
+	while i < chars.len() {
+		let c = chars[i];
+		if c == '/' && chars.get(i + 1) == Some(&'/') {
+			let rest: String = chars[i..].iter().collect();
+			html.push_str(&format!("<span class=\"com\">{}</span>", escape(&rest)));
+			break;
+		}
+		if c == '"' {
+			let mut end = i + 1;
+			while end < chars.len() && chars[end] != '"' {
+				end += if chars[end] == '\\' { 2 } else { 1 };
+			}
+			let end = end.min(chars.len() - 1);
+			let literal: String = chars[i..end + 1].iter().collect();
+			html.push_str(&format!("<span class=\"str\">{}</span>", escape(&literal)));
+			i = end + 1;
+			continue;
+		}
+		if c.is_alphanumeric() || c == '_' {
+			let mut end = i;
+			while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+				end += 1;
+			}
+			let word: String = chars[i..end].iter().collect();
+			if KEYWORDS.contains(&word.as_str()) {
+				html.push_str(&format!("<span class=\"kw\">{}</span>", word));
+			} else if c.is_ascii_digit() {
+				html.push_str(&format!("<span class=\"num\">{}</span>", word));
+			} else {
+				html.push_str(&word);
+			}
+			i = end;
+			continue;
+		}
+		html.push_str(&escape(&c.to_string()));
+		i += 1;
+	}
+	return html;
+}
@@ -0,0 +1,37 @@
+//! Mapping of throwables to HTTP status codes
+use std::io;
+use {Throwable, Exception};
+
+/// Represent a `Throwable` which can be mapped to an HTTP status code, to be sent back to the
+/// client when it reaches a request handler
+pub trait HttpStatus: Throwable {
+	/// Get the HTTP status code matching this `Throwable`. Defaults to 500 (Internal Server Error)
+	fn http_status(&self) -> u16 {
+		return 500;
+	}
+}
+
+/// The status is the one set with `set_http_status`, or else derived from the `io::Error`
+/// the `Exception` was created from, if any
+impl HttpStatus for Exception {
+	fn http_status(&self) -> u16 {
+		if let Some(status) = self.http_status {
+			return status;
+		}
+		return match self.error().and_then(|e| e.downcast_ref::<io::Error>()).map(|e| e.kind()) {
+			Some(io::ErrorKind::NotFound) => 404,
+			Some(io::ErrorKind::PermissionDenied) => 403,
+			Some(io::ErrorKind::AlreadyExists) => 409,
+			Some(io::ErrorKind::InvalidInput) | Some(io::ErrorKind::InvalidData) => 400,
+			Some(io::ErrorKind::TimedOut) => 504,
+			_ => 500
+		};
+	}
+}
+
+impl Exception {
+	/// Set the HTTP status code returned by `http_status()`
+	pub fn set_http_status(&mut self, status: u16) {
+		self.http_status = Some(status);
+	}
+}
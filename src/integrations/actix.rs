@@ -0,0 +1,42 @@
+//! Integration with `actix-web`, enabled by the `actix` feature
+use std::sync::RwLock;
+use ::actix_web::{HttpResponse, ResponseError};
+use ::actix_web::http::StatusCode;
+use {Exception, HttpStatus};
+use super::problem_json;
+
+type StatusMapper = fn(&Exception) -> StatusCode;
+type BodyRenderer = fn(&Exception, StatusCode) -> HttpResponse;
+
+static STATUS_MAPPER: RwLock<Option<StatusMapper>> = RwLock::new(None);
+static BODY_RENDERER: RwLock<Option<BodyRenderer>> = RwLock::new(None);
+
+/// Set the function mapping exceptions to the status of actix responses. By default, the
+/// status returned by `http_status()` is used
+pub fn set_actix_status_mapper(mapper: fn(&Exception) -> StatusCode) {
+	*STATUS_MAPPER.write().unwrap_or_else(|e| e.into_inner()) = Some(mapper);
+}
+
+/// Set the function rendering the actix responses for exceptions. By default, the body is an
+/// `application/problem+json` document, including the stack trace in debug builds only
+pub fn set_actix_body_renderer(renderer: fn(&Exception, StatusCode) -> HttpResponse) {
+	*BODY_RENDERER.write().unwrap_or_else(|e| e.into_inner()) = Some(renderer);
+}
+
+impl ResponseError for Exception {
+	fn status_code(&self) -> StatusCode {
+		return match *STATUS_MAPPER.read().unwrap_or_else(|e| e.into_inner()) {
+			Some(mapper) => mapper(self),
+			None => StatusCode::from_u16(self.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+		};
+	}
+	
+	fn error_response(&self) -> HttpResponse {
+		let status = self.status_code();
+		if let Some(renderer) = *BODY_RENDERER.read().unwrap_or_else(|e| e.into_inner()) {
+			return renderer(self, status);
+		}
+		let body = problem_json(self, status.as_u16(), status.canonical_reason().unwrap_or("Unknown"));
+		return HttpResponse::build(status).content_type("application/problem+json").body(body);
+	}
+}
@@ -0,0 +1,34 @@
+//! Interoperability with `anyhow`, enabled by the `anyhow` feature
+use ::anyhow::Error;
+use {Throwable, IntoThrowable, Exception, exception_from_messages};
+
+/// Convert the `anyhow::Error` into an `Exception`, its chain of errors becoming the causes
+impl IntoThrowable<Exception> for Error {
+	fn into_throwable(self) -> Exception {
+		return exception_from_messages(self.chain().map(|e| e.to_string()).collect());
+	}
+}
+
+/// Convert the `Exception` into an `anyhow::Error`. Each `Throwable` of the cause chain becomes
+/// an `anyhow` context, made of its message and its rendered stack trace entries
+impl From<Exception> for Error {
+	fn from(e: Exception) -> Error {
+		let mut levels: Vec<String> = Vec::new();
+		levels.push(render(&e));
+		levels.extend(e.causes().map(render));
+		let mut err = Error::msg(levels.pop().unwrap_or_default());
+		while let Some(level) = levels.pop() {
+			err = err.context(level);
+		}
+		return err;
+	}
+}
+
+/// Render the message and the stack trace entries of `th`, without its causes
+fn render<T: Throwable+?Sized>(th: &T) -> String {
+	let mut out = th.get_message().to_string();
+	for s in th.frames() {
+		out.push_str(&format!("\n\t{}", s));
+	}
+	return out;
+}
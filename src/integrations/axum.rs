@@ -0,0 +1,15 @@
+//! Integration with `axum`, enabled by the `axum` feature
+use ::axum::http::{StatusCode, header};
+use ::axum::response::{IntoResponse, Response};
+use {Exception, HttpStatus};
+use super::problem_json;
+
+/// Respond with the status returned by `http_status()`, and an `application/problem+json` body
+/// describing the `Exception`. The stack trace is included in debug builds only
+impl IntoResponse for Exception {
+	fn into_response(self) -> Response {
+		let status = StatusCode::from_u16(self.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+		let body = problem_json(&self, status.as_u16(), status.canonical_reason().unwrap_or("Unknown"));
+		return (status, [(header::CONTENT_TYPE, "application/problem+json")], body).into_response();
+	}
+}
@@ -0,0 +1,93 @@
+//! Interoperability with `eyre`, enabled by the `eyre` feature
+use std::error::Error;
+use std::fmt;
+use ::eyre::{Report, EyreHandler, InstallError};
+use {Throwable, IntoThrowable, Exception, StackEntry, StackTrace};
+
+/// Error holding the message and the stack trace entries of one `Throwable` of a cause chain,
+/// so they are still available once converted into an `eyre::Report`
+#[derive(Debug)]
+struct Level {
+	message: String,
+	frames: Vec<StackEntry>,
+	source: Option<Box<Level>>
+}
+
+impl fmt::Display for Level {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return f.write_str(&self.message);
+	}
+}
+
+impl Error for Level {
+	fn source(&self) -> Option<&(Error + 'static)> {
+		return self.source.as_ref().map(|s| &**s as &(Error + 'static));
+	}
+}
+
+/// Convert the `eyre::Report` into an `Exception`, its chain of errors becoming the causes.
+/// Stack trace entries are restored for reports created from an `Exception`
+impl IntoThrowable<Exception> for Report {
+	fn into_throwable(self) -> Exception {
+		let mut levels: Vec<(String, Vec<StackEntry>)> = self.chain().map(|e| {
+			let frames = e.downcast_ref::<Level>().map(|l| l.frames.clone()).unwrap_or_default();
+			(e.to_string(), frames)
+		}).collect();
+		let mut e: Option<Exception> = None;
+		while let Some((message, frames)) = levels.pop() {
+			let mut outer = match e {
+				Some(cause) => Exception::new_with_cause(message, cause),
+				None => Exception::new(message)
+			};
+			outer.stack = StackTrace::from(frames);
+			e = Some(outer);
+		}
+		return e.unwrap_or_else(|| Exception::new(String::new()));
+	}
+}
+
+/// Convert the `Exception` into an `eyre::Report`, keeping the messages and stack trace entries
+/// of the whole cause chain. They are rendered by `TraceHandler`
+impl From<Exception> for Report {
+	fn from(e: Exception) -> Report {
+		let mut levels: Vec<&Throwable> = vec!(&e);
+		levels.extend(e.causes());
+		let mut level: Option<Box<Level>> = None;
+		while let Some(th) = levels.pop() {
+			level = Some(Box::new(Level{
+				message: th.get_message().to_string(),
+				frames: th.frames().cloned().collect(),
+				source: level
+			}));
+		}
+		return Report::new(*level.unwrap());
+	}
+}
+
+/// `EyreHandler` rendering reports like `Throwable::print_stack_trace` does, including
+/// the stack trace entries collected by the macros
+pub struct TraceHandler;
+
+impl EyreHandler for TraceHandler {
+	fn debug(&self, error: &(Error + 'static), f: &mut fmt::Formatter) -> fmt::Result {
+		let mut current = Some(error);
+		while let Some(err) = current {
+			if !::std::ptr::eq(err, error) {
+				write!(f, "\nCaused by: ")?;
+			}
+			write!(f, "{}", err)?;
+			if let Some(level) = err.downcast_ref::<Level>() {
+				for s in &level.frames {
+					write!(f, "\n\t{}", s)?;
+				}
+			}
+			current = err.source();
+		}
+		return Ok(());
+	}
+}
+
+/// Install `TraceHandler` as the global `eyre` report handler
+pub fn install_eyre_handler() -> Result<(), InstallError> {
+	return ::eyre::set_hook(Box::new(|_| Box::new(TraceHandler)));
+}
@@ -0,0 +1,42 @@
+//! Logging of stack traces through the `log` crate, enabled by the `log` feature
+use ::log::{Level, log};
+use Throwable;
+use format::Formatter;
+use sink::Sink;
+
+/// Log the stack trace of `th` as a single record at the given level, with the `exceptions`
+/// target
+pub fn log_trace(th: &Throwable, level: Level) {
+	log!(target: "exceptions", level, "{}", Formatter::configured().render(th).trim_end());
+}
+
+/// `Sink` logging stack traces through the `log` crate
+pub struct LogSink {
+	level: Level
+}
+
+/// Same as `LogSink::new()`
+impl Default for LogSink {
+	fn default() -> LogSink {
+		return LogSink::new();
+	}
+}
+
+impl LogSink {
+	/// Create a sink logging at the error level
+	pub fn new() -> LogSink {
+		return LogSink{level: Level::Error};
+	}
+	
+	/// Log at `level` instead
+	pub fn level(mut self, level: Level) -> LogSink {
+		self.level = level;
+		return self;
+	}
+}
+
+impl Sink for LogSink {
+	fn write_trace(&self, th: &Throwable) {
+		log_trace(th, self.level);
+	}
+}
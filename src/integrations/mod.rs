@@ -0,0 +1,37 @@
+//! Conversions from and to the error types of other crates, enabled with cargo features
+#[cfg(any(feature = "axum", feature = "actix"))]
+use {Throwable, Exception, write_stack_trace};
+
+#[cfg(feature = "anyhow")]
+mod anyhow;
+#[cfg(feature = "eyre")]
+pub mod eyre;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "tonic")]
+pub mod tonic;
+#[cfg(feature = "log")]
+pub mod log;
+
+/// Render `e` as an `application/problem+json` document (RFC 7807) with the given status.
+/// The stack trace is included in debug builds only
+#[cfg(any(feature = "axum", feature = "actix"))]
+fn problem_json(e: &Exception, status: u16, title: &str) -> String {
+	let mut problem = ::serde_json::json!({
+		"type": "about:blank",
+		"title": title,
+		"status": status,
+		"detail": e.get_message()
+	});
+	if cfg!(debug_assertions) {
+		let mut trace = Vec::new();
+		let _ = write_stack_trace(e, &mut trace);
+		let lines: Vec<::serde_json::Value> = String::from_utf8_lossy(&trace).lines().map(|l| l.into()).collect();
+		problem["trace"] = ::serde_json::Value::Array(lines);
+	}
+	return problem.to_string();
+}
@@ -0,0 +1,73 @@
+//! Conversions between `Exception` and `tonic::Status`, enabled by the `tonic` feature
+use ::tonic::{Code, Status};
+use {Throwable, IntoThrowable, Exception, HttpStatus, binary};
+
+/// Get the gRPC code matching the kind of `th`, if it's a known one
+fn code_from_kind<T: Throwable+?Sized>(th: &T) -> Option<Code> {
+	let codes = [
+		("io.not_found", Code::NotFound),
+		("io.permission_denied", Code::PermissionDenied),
+		("io.already_exists", Code::AlreadyExists),
+		("io.invalid", Code::InvalidArgument),
+		("io.timeout", Code::DeadlineExceeded)
+	];
+	return codes.iter().find(|&&(kind, _)| th.is_instance_of(kind)).map(|&(_, code)| code);
+}
+
+/// Get the gRPC code matching an HTTP status code
+fn code_from_http_status(status: u16) -> Code {
+	return match status {
+		400 => Code::InvalidArgument,
+		401 => Code::Unauthenticated,
+		403 => Code::PermissionDenied,
+		404 => Code::NotFound,
+		409 => Code::AlreadyExists,
+		429 => Code::ResourceExhausted,
+		499 => Code::Cancelled,
+		501 => Code::Unimplemented,
+		503 => Code::Unavailable,
+		504 => Code::DeadlineExceeded,
+		_ => Code::Internal
+	};
+}
+
+/// Get the HTTP status code matching a gRPC code
+fn http_status_from_code(code: Code) -> u16 {
+	return match code {
+		Code::InvalidArgument | Code::OutOfRange | Code::FailedPrecondition => 400,
+		Code::Unauthenticated => 401,
+		Code::PermissionDenied => 403,
+		Code::NotFound => 404,
+		Code::AlreadyExists | Code::Aborted => 409,
+		Code::ResourceExhausted => 429,
+		Code::Cancelled => 499,
+		Code::Unimplemented => 501,
+		Code::Unavailable => 503,
+		Code::DeadlineExceeded => 504,
+		_ => 500
+	};
+}
+
+/// Convert the `Exception` into a `Status` with the code matching `kind()`, or else the one
+/// matching `http_status()`. The exception and its causes are stored in the status details,
+/// encoded by the `binary` module
+impl From<Exception> for Status {
+	fn from(e: Exception) -> Status {
+		let code = code_from_kind(&e).unwrap_or_else(|| code_from_http_status(e.http_status()));
+		return Status::with_details(code, e.get_message(), binary::encode(&e).into());
+	}
+}
+
+/// Convert the `Status` into an `Exception`, with the HTTP status matching its code. If the
+/// status details hold an exception encoded by the `binary` module, like the statuses converted
+/// from an `Exception`, it's decoded with its stack trace entries and causes
+impl IntoThrowable<Exception> for Status {
+	fn into_throwable(self) -> Exception {
+		let mut e = match binary::decode(self.details()) {
+			Ok(remote) => remote,
+			Err(_) => Exception::new(self.message().to_string())
+		};
+		e.set_http_status(http_status_from_code(self.code()));
+		return e;
+	}
+}
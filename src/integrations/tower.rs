@@ -0,0 +1,116 @@
+//! `tower` middleware catching the errors of services, enabled by the `tower` feature
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use ::tower_layer::Layer;
+use ::tower_service::Service;
+use {Throwable, IntoThrowable, Exception, sink};
+
+static NEXT_CORRELATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// `Layer` converting the errors of the wrapped service into `Throwable`s of type `T`. Each
+/// error is given a correlation id, written with its stack trace to the sink set with
+/// `sink::set_sink`, then converted into the response error type by the mapping function
+/// 
+/// ```
+/// # use exceptions::prelude::*;
+/// # use exceptions::ExceptionLayer;
+/// # struct MyError { id: u64 }
+/// # impl MyError {
+/// #     fn internal(id: u64) -> MyError { return MyError{id: id}; }
+/// # }
+/// let layer = ExceptionLayer::<Exception, _>::new(|e: Exception, id: u64| MyError::internal(id));
+/// ```
+pub struct ExceptionLayer<T, F> {
+	map: F,
+	_throwable: PhantomData<fn() -> T>
+}
+
+impl <T, F: Clone> ExceptionLayer<T, F> {
+	/// Create a layer mapping the exceptions and their correlation id with `map`
+	pub fn new(map: F) -> ExceptionLayer<T, F> {
+		return ExceptionLayer{map: map, _throwable: PhantomData};
+	}
+}
+
+impl <T, F: Clone> Clone for ExceptionLayer<T, F> {
+	fn clone(&self) -> ExceptionLayer<T, F> {
+		return ExceptionLayer::new(self.map.clone());
+	}
+}
+
+impl <S, T, F: Clone> Layer<S> for ExceptionLayer<T, F> {
+	type Service = ExceptionService<S, T, F>;
+	
+	fn layer(&self, inner: S) -> ExceptionService<S, T, F> {
+		return ExceptionService{inner: inner, map: self.map.clone(), _throwable: PhantomData};
+	}
+}
+
+/// `Service` created by `ExceptionLayer`
+pub struct ExceptionService<S, T, F> {
+	inner: S,
+	map: F,
+	_throwable: PhantomData<fn() -> T>
+}
+
+impl <S: Clone, T, F: Clone> Clone for ExceptionService<S, T, F> {
+	fn clone(&self) -> ExceptionService<S, T, F> {
+		return ExceptionService{inner: self.inner.clone(), map: self.map.clone(), _throwable: PhantomData};
+	}
+}
+
+impl <S, T, F, E, R> Service<R> for ExceptionService<S, T, F>
+	where S: Service<R>, S::Error: IntoThrowable<T>, T: Throwable, F: Fn(Exception, u64) -> E + Clone {
+	type Response = S::Response;
+	type Error = E;
+	type Future = ExceptionFuture<S::Future, T, F>;
+	
+	fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), E>> {
+		return match self.inner.poll_ready(cx) {
+			Poll::Ready(Err(e)) => Poll::Ready(Err(handle(e, &self.map, "ExceptionService::poll_ready()"))),
+			Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+			Poll::Pending => Poll::Pending
+		};
+	}
+	
+	fn call(&mut self, request: R) -> ExceptionFuture<S::Future, T, F> {
+		return ExceptionFuture{inner: self.inner.call(request), map: self.map.clone(), _throwable: PhantomData};
+	}
+}
+
+/// `Future` returned by `ExceptionService`
+pub struct ExceptionFuture<Fut, T, F> {
+	inner: Fut,
+	map: F,
+	_throwable: PhantomData<fn() -> T>
+}
+
+impl <Fut, T, F, E, V, X> Future for ExceptionFuture<Fut, T, F>
+	where Fut: Future<Output = Result<V, X>>, X: IntoThrowable<T>, T: Throwable, F: Fn(Exception, u64) -> E {
+	type Output = Result<V, E>;
+	
+	fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<V, E>> {
+		// Safe because `inner` is never moved out of the pinned future
+		let this = unsafe { self.get_unchecked_mut() };
+		let inner = unsafe { Pin::new_unchecked(&mut this.inner) };
+		return match inner.poll(cx) {
+			Poll::Ready(Err(e)) => Poll::Ready(Err(handle(e, &this.map, "ExceptionService::call()"))),
+			Poll::Ready(Ok(v)) => Poll::Ready(Ok(v)),
+			Poll::Pending => Poll::Pending
+		};
+	}
+}
+
+/// Record where the error left the service as a boundary labeled `label`, since the location in
+/// this module wouldn't help, then write and map it
+fn handle<X, T, F, E>(e: X, map: &F, label: &'static str) -> E where X: IntoThrowable<T>, T: Throwable, F: Fn(Exception, u64) -> E {
+	let mut th = e.into_throwable();
+	th.push_boundary(label);
+	let id = NEXT_CORRELATION_ID.fetch_add(1, Ordering::Relaxed);
+	let e = Exception::new_with_cause(format!("Service failure (correlation id {})", id), th);
+	sink::write_trace(&e);
+	return map(e, id);
+}
@@ -0,0 +1,122 @@
+//! Links opening the "new issue" page of a GitHub or GitLab project, with the title and the
+//! body prefilled from an exception, so that command line tools can offer to report a bug
+//! when an uncaught exception occurs
+//! 
+//! ```
+//! use exceptions::Exception;
+//! use exceptions::issue::IssueUrl;
+//! 
+//! let e = Exception::new("Cannot load configuration".to_string());
+//! let url = IssueUrl::github("phsym/exceptions-rs").label("bug").url(&e);
+//! assert!(url.starts_with("https://github.com/phsym/exceptions-rs/issues/new?title=Cannot%20load%20configuration"));
+//! ```
+use Throwable;
+use format::{Formatter, Style};
+
+/// Hosting service of a project
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Forge {
+	/// github.com, or a GitHub Enterprise server
+	GitHub,
+	/// gitlab.com, or a self-hosted GitLab instance
+	GitLab
+}
+
+/// Builder of "new issue" links
+#[derive(Clone, Debug)]
+pub struct IssueUrl {
+	forge: Forge,
+	host: String,
+	repo: String,
+	template: Option<String>,
+	labels: Vec<String>,
+	formatter: Formatter
+}
+
+impl IssueUrl {
+	/// Create a builder of links to a `owner/name` GitHub repository
+	pub fn github(repo: &str) -> IssueUrl {
+		return IssueUrl::new(Forge::GitHub, "https://github.com", repo);
+	}
+	
+	/// Create a builder of links to a `group/name` project on gitlab.com
+	pub fn gitlab(repo: &str) -> IssueUrl {
+		return IssueUrl::new(Forge::GitLab, "https://gitlab.com", repo);
+	}
+	
+	fn new(forge: Forge, host: &str, repo: &str) -> IssueUrl {
+		// Long traces would make links exceeding what browsers and servers accept
+		let formatter = Formatter::new().style(Style::Markdown).max_frames(20);
+		return IssueUrl{forge: forge, host: host.to_string(), repo: repo.trim_matches('/').to_string(), template: None, labels: Vec::new(), formatter: formatter};
+	}
+	
+	/// Set the base URL of the server, for GitHub Enterprise or self-hosted GitLab instances
+	pub fn host(mut self, host: &str) -> IssueUrl {
+		self.host = host.trim_end_matches('/').to_string();
+		return self;
+	}
+	
+	/// Use the issue template `name` of the project
+	pub fn template(mut self, name: &str) -> IssueUrl {
+		self.template = Some(name.to_string());
+		return self;
+	}
+	
+	/// Add a label to the issue
+	pub fn label(mut self, label: &str) -> IssueUrl {
+		self.labels.push(label.to_string());
+		return self;
+	}
+	
+	/// Set the formatter rendering the body of the issue, which is a Markdown one with at most
+	/// 20 stack trace entries per `Throwable` by default
+	pub fn formatter(mut self, formatter: Formatter) -> IssueUrl {
+		self.formatter = formatter;
+		return self;
+	}
+	
+	/// Build the link to report `th`, titled with the first line of its message
+	pub fn url<T: Throwable+?Sized>(&self, th: &T) -> String {
+		let title = th.get_message().lines().next().unwrap_or("");
+		let body = self.formatter.render(th);
+		let mut params: Vec<(&str, String)> = Vec::new();
+		let path = match self.forge {
+			Forge::GitHub => {
+				params.push(("title", title.to_string()));
+				params.push(("body", body));
+				if let Some(ref template) = self.template {
+					params.push(("template", template.clone()));
+				}
+				if !self.labels.is_empty() {
+					params.push(("labels", self.labels.join(",")));
+				}
+				"issues/new"
+			},
+			Forge::GitLab => {
+				params.push(("issue[title]", title.to_string()));
+				params.push(("issue[description]", body));
+				if let Some(ref template) = self.template {
+					params.push(("issuable_template", template.clone()));
+				}
+				for label in &self.labels {
+					params.push(("issue[label_names][]", label.clone()));
+				}
+				"-/issues/new"
+			}
+		};
+		let query: Vec<String> = params.iter().map(|&(key, ref value)| format!("{}={}", encode(key), encode(value))).collect();
+		return format!("{}/{}/{}?{}", self.host, self.repo, path, query.join("&"));
+	}
+}
+
+/// Percent-encode `text` for a query string
+fn encode(text: &str) -> String {
+	let mut encoded = String::with_capacity(text.len());
+	for b in text.bytes() {
+		match b {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(b as char),
+			b => encoded.push_str(&format!("%{:02X}", b))
+		}
+	}
+	return encoded;
+}
@@ -0,0 +1,104 @@
+//! JSON serialization of exceptions, enabled by the `json` feature. A serialized `Throwable` is
+//! an object with a `message` string, an optional `kind` string, a `frames` array of objects
+//! having `file`, `line`, `expr` and `repeat` members, an optional `context` array of objects
+//! having `key` and `value` string members, and an optional `cause` object serialized the same way
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use ::serde_json::{Value, Map};
+use {Throwable, Exception, StackEntry, StackTrace};
+
+/// Serialize `th` and its causes into a JSON value
+pub fn to_value<T: Throwable+?Sized>(th: &T) -> Value {
+	let mut obj = Map::new();
+	obj.insert("message".to_string(), Value::from(th.get_message()));
+	if !th.kind().is_empty() {
+		obj.insert("kind".to_string(), Value::from(th.kind()));
+	}
+	let frames: Vec<Value> = th.frames().map(|f| {
+		let mut frame = Map::new();
+		frame.insert("file".to_string(), Value::from(&*f.file));
+		frame.insert("line".to_string(), Value::from(f.line));
+		frame.insert("expr".to_string(), Value::from(&*f.expr));
+		frame.insert("repeat".to_string(), Value::from(f.repeat));
+		Value::Object(frame)
+	}).collect();
+	obj.insert("frames".to_string(), Value::Array(frames));
+	if !th.get_context().is_empty() {
+		let context: Vec<Value> = th.get_context().iter().map(|(k, v)| {
+			let mut entry = Map::new();
+			entry.insert("key".to_string(), Value::from(k.as_str()));
+			entry.insert("value".to_string(), Value::from(v.as_str()));
+			Value::Object(entry)
+		}).collect();
+		obj.insert("context".to_string(), Value::Array(context));
+	}
+	if let Some(cause) = th.get_cause() {
+		obj.insert("cause".to_string(), to_value(cause));
+	}
+	return Value::Object(obj);
+}
+
+/// Serialize `th` and its causes into a JSON string
+pub fn to_json<T: Throwable+?Sized>(th: &T) -> String {
+	return to_value(th).to_string();
+}
+
+/// Deserialize an `Exception` and its causes from a JSON value
+pub fn from_value(value: &Value) -> Result<Exception, Exception> {
+	let message = match value.get("message").and_then(|m| m.as_str()) {
+		Some(message) => message.to_string(),
+		None => return Err(Exception::new("Invalid serialized exception: missing message".to_string()))
+	};
+	let mut e = match value.get("cause") {
+		Some(cause) if !cause.is_null() => Exception::new_with_cause(message, from_value(cause)?),
+		_ => Exception::new(message)
+	};
+	if let Some(kind) = value.get("kind").and_then(|k| k.as_str()) {
+		e.set_kind(kind);
+	}
+	if let Some(context) = value.get("context").and_then(|c| c.as_array()) {
+		for entry in context {
+			match (entry.get("key").and_then(|k| k.as_str()), entry.get("value").and_then(|v| v.as_str())) {
+				(Some(key), Some(value)) => e.add_context(key, value.to_string()),
+				_ => return Err(Exception::new(format!("Invalid serialized context entry: {}", entry)))
+			}
+		}
+	}
+	if let Some(frames) = value.get("frames").and_then(|f| f.as_array()) {
+		let mut entries = Vec::new();
+		for frame in frames {
+			entries.push(frame_from_value(frame)?);
+		}
+		e.stack = StackTrace::from(entries);
+	}
+	return Ok(e);
+}
+
+/// Deserialize an `Exception` and its causes from a JSON string
+pub fn from_json(json: &str) -> Result<Exception, Exception> {
+	let value: Value = match ::serde_json::from_str(json) {
+		Ok(value) => value,
+		Err(e) => return Err(Exception::new_with_cause("Invalid serialized exception".to_string(), Exception::from_error(e)))
+	};
+	return from_value(&value);
+}
+
+fn frame_from_value(value: &Value) -> Result<StackEntry, Exception> {
+	let file = value.get("file").and_then(|f| f.as_str());
+	let line = value.get("line").and_then(|l| l.as_u64());
+	let expr = value.get("expr").and_then(|e| e.as_str());
+	let repeat = value.get("repeat").and_then(|r| r.as_u64()).unwrap_or(1);
+	return match (file, line, expr) {
+		(Some(file), Some(line), Some(expr)) => Ok(StackEntry{file: Cow::Owned(file.to_string()), line: line as u32, expr: Cow::Owned(expr.to_string()), repeat: repeat as u32}),
+		_ => Err(Exception::new(format!("Invalid serialized stack trace entry: {}", value)))
+	};
+}
+
+/// Deserialize an exception serialized with `to_value`
+impl <'a> TryFrom<&'a Value> for Exception {
+	type Error = Exception;
+	
+	fn try_from(value: &'a Value) -> Result<Exception, Exception> {
+		return from_value(value);
+	}
+}
@@ -1,29 +1,328 @@
 //! Crate for emulating some exception-like behaviour. It gives the capability to have a 
 //! detailed stack trace in errors, making it easier to trace where and why failures happened.
+#![cfg_attr(feature = "nightly", feature(try_trait_v2, try_trait_v2_residual))]
+// `Result<T, Exception>` is how every failure is returned, boxing would cost an allocation per error
+#![allow(clippy::result_large_err)]
 use std::string::ToString;
 use std::io;
 use std::io::{stdout, stderr, Write};
 use std::fmt;
+use std::error;
 use std::ops::Deref;
+use std::any;
+use std::any::Any;
+use std::slice;
+use std::num;
+use std::net;
+use std::str;
+use std::string;
+use std::panic;
+use std::process;
+use std::mem;
+use std::sync;
+use std::sync::mpsc;
+use std::borrow::Cow;
+use std::convert;
+use std::convert::TryFrom;
+
+pub mod translate;
+pub mod sink;
+pub mod metrics;
+pub mod build;
+pub mod config;
+pub mod filter;
+pub mod format;
+pub mod grouping;
+pub mod prelude;
+pub mod pool;
+pub mod report;
+pub mod testing;
+pub mod context;
+pub mod source_map;
+pub mod binary;
+pub mod html;
+pub mod issue;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "json")]
+pub mod subprocess;
+#[cfg(feature = "nightly")]
+pub mod nightly;
+mod http_status;
+mod integrations;
+
+pub use http_status::HttpStatus;
+
+#[cfg(feature = "anyhow")]
+extern crate anyhow;
+#[cfg(feature = "eyre")]
+extern crate eyre;
+#[cfg(feature = "axum")]
+extern crate axum;
+#[cfg(feature = "actix")]
+extern crate actix_web;
+#[cfg(any(feature = "json", feature = "axum", feature = "actix"))]
+extern crate serde_json;
+#[cfg(feature = "tower")]
+extern crate tower_layer;
+#[cfg(feature = "tower")]
+extern crate tower_service;
+#[cfg(feature = "tonic")]
+extern crate tonic;
+#[cfg(feature = "reporter")]
+extern crate ureq;
+#[cfg(feature = "macros")]
+extern crate exceptions_macros;
+#[cfg(feature = "gzip")]
+extern crate flate2;
+#[cfg(feature = "log")]
+extern crate log;
+#[cfg(feature = "regex")]
+extern crate regex_lite;
+
+#[cfg(feature = "macros")]
+pub use exceptions_macros::{throws, IntoThrowable, try_spanned, throw_spanned};
+#[cfg(feature = "eyre")]
+pub use integrations::eyre::{TraceHandler, install_eyre_handler};
+#[cfg(feature = "actix")]
+pub use integrations::actix::{set_actix_status_mapper, set_actix_body_renderer};
+#[cfg(feature = "tower")]
+pub use integrations::tower::{ExceptionLayer, ExceptionService, ExceptionFuture};
+#[cfg(feature = "log")]
+pub use integrations::log::{LogSink, log_trace};
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub use log::Level as __LogLevel;
 
 /// Represent an entry in a stack trace
+#[derive(Clone, Debug)]
 pub struct StackEntry {
-	/// The file where the trace was recorded
-	pub file: &'static str,
+	/// The file where the trace was recorded. It's owned for entries rebuilt at runtime, for
+	/// example when deserializing exceptions
+	pub file: Cow<'static, str>,
 	/// The line in the file
 	pub line: u32,
-	/// The code expression which caused the `Throwable`
-	pub expr: &'static str
+	/// The code expression which caused the `Throwable`, or the label of a boundary
+	pub expr: Cow<'static, str>,
+	/// The number of consecutive times this entry was recorded, for example by a recursive call
+	pub repeat: u32
+}
+
+impl StackEntry {
+	/// Check if `self` and `other` were recorded at the same place
+	pub fn same_location(&self, other: &StackEntry) -> bool {
+		return self.file == other.file && self.line == other.line && self.expr == other.expr;
+	}
+	
+	/// Check if this entry is a boundary recorded with `Throwable::push_boundary`, rather than a
+	/// location. Boundaries have an empty file and a line 0, and their label as expression
+	pub fn is_boundary(&self) -> bool {
+		return self.file.is_empty() && self.line == 0;
+	}
+}
+
+impl fmt::Display for StackEntry {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "at {} [{}:{}]", self.expr, self.file, self.line)?;
+		if self.repeat > 1 {
+			write!(f, " (repeated {} times)", self.repeat)?;
+		}
+		return Ok(());
+	}
+}
+
+/// Entries of a stack trace, from the outermost to the innermost one
+#[derive(Clone, Debug, Default)]
+pub struct StackTrace {
+	entries: Vec<StackEntry>
+}
+
+impl StackTrace {
+	/// Create an empty stack trace
+	pub fn new() -> StackTrace {
+		return StackTrace{entries: Vec::new()};
+	}
+	
+	/// Get the number of entries
+	pub fn len(&self) -> usize {
+		return self.entries.len();
+	}
+	
+	/// Check if there are no entries
+	pub fn is_empty(&self) -> bool {
+		return self.entries.is_empty();
+	}
+	
+	/// Get an iterator over the entries, from the outermost to the innermost one
+	pub fn iter(&self) -> Frames<'_> {
+		return Frames::new(&self.entries);
+	}
+	
+	/// Get the innermost entry, which is the closest to where the `Throwable` was raised
+	pub fn top(&self) -> Option<&StackEntry> {
+		return self.entries.last();
+	}
+	
+	/// Get the entries as a slice, from the outermost to the innermost one
+	pub fn as_slice(&self) -> &[StackEntry] {
+		return &self.entries;
+	}
+	
+	/// Record `entry` as the new outermost one. If it was recorded at the same place as the
+	/// current outermost one, the latter is counted as repeated instead
+	pub fn push(&mut self, entry: StackEntry) {
+		if let Some(outer) = self.entries.first_mut() {
+			if outer.same_location(&entry) {
+				outer.repeat += 1;
+				return;
+			}
+		}
+		self.entries.insert(0, entry);
+	}
+	
+	/// Get an iterator over mutable references to the entries, from the outermost to the
+	/// innermost one
+	pub fn iter_mut(&mut self) -> slice::IterMut<'_, StackEntry> {
+		return self.entries.iter_mut();
+	}
+	
+	/// Keep only the entries for which `predicate` returns `true`
+	pub fn retain<P: FnMut(&StackEntry) -> bool>(&mut self, predicate: P) {
+		self.entries.retain(predicate);
+	}
+	
+	/// Keep only the `len` innermost entries, which are the closest to where the `Throwable`
+	/// was raised
+	pub fn truncate(&mut self, len: usize) {
+		if self.entries.len() > len {
+			let dropped = self.entries.len() - len;
+			self.entries.drain(..dropped);
+		}
+	}
+}
+
+/// Numbers of stack trace entries a `Throwable` dropped, like the ones exceeding the maximum
+/// set with `Exception::set_max_frames`, by position
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct DroppedFrames {
+	/// Number of dropped entries which were before the outermost kept one
+	pub outermost: usize,
+	/// Number of dropped entries which were between kept ones
+	pub middle: usize,
+	/// Index of the kept entry the middle entries were before
+	pub middle_at: usize
+}
+
+impl DroppedFrames {
+	/// Get the total number of dropped entries
+	pub fn total(&self) -> usize {
+		return self.outermost + self.middle;
+	}
+}
+
+/// Stack trace returned by the default implementation of `Throwable::get_stack_trace`
+static EMPTY_STACK_TRACE: StackTrace = StackTrace{entries: Vec::new()};
+
+impl From<Vec<StackEntry>> for StackTrace {
+	/// The entries are given from the outermost to the innermost one
+	fn from(entries: Vec<StackEntry>) -> StackTrace {
+		return StackTrace{entries: entries};
+	}
+}
+
+impl <'a> IntoIterator for &'a StackTrace {
+	type Item = &'a StackEntry;
+	type IntoIter = Frames<'a>;
+	
+	fn into_iter(self) -> Frames<'a> {
+		return self.iter();
+	}
+}
+
+/// Render one entry per line, indented by a tab, like in `Throwable::print_stack_trace`
+impl fmt::Display for StackTrace {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for entry in &self.entries {
+			writeln!(f, "\t{}", entry)?;
+		}
+		return Ok(());
+	}
+}
+
+/// Give access to `self` as `Any`, so `Throwable` trait objects can be downcast to their
+/// concrete type. It's implemented for every `'static` type
+pub trait AsAny: Any {
+	/// Get `self` as `Any`
+	fn as_any(&self) -> &Any;
+}
+
+impl <T: Any> AsAny for T {
+	fn as_any(&self) -> &Any {
+		return self;
+	}
+}
+
+/// Give access to `self` as a `Throwable` trait object, including from the default methods of
+/// `Throwable`, which can't coerce `self`. It's implemented for every `Throwable`
+pub trait AsThrowable {
+	/// Get `self` as a `Throwable` trait object
+	fn as_throwable(&self) -> &Throwable;
+}
+
+impl <T: Throwable> AsThrowable for T {
+	fn as_throwable(&self) -> &Throwable {
+		return self;
+	}
+}
+
+/// Tracing of the error of a `Result` without the macros of this crate
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn parse(text: &str) -> Result<u32, Exception> { throw!(format!("Invalid number {}", text)); }
+/// # fn load(text: &str) -> Result<u32, Exception> {
+/// let config = parse(&text).traced()?;
+/// # return Ok(config);
+/// # }
+/// # assert_eq!(load("x").unwrap_err().frame_count(), 2);
+/// ```
+pub trait Traced {
+	/// Record the calling location in the stack trace of the error, if any
+	#[track_caller]
+	fn traced(self) -> Self;
+}
+
+impl <T, E: Throwable> Traced for Result<T, E> {
+	#[track_caller]
+	fn traced(self) -> Self {
+		// The location isn't tracked inside of the closure
+		let location = panic::Location::caller();
+		return self.map_err(|mut e| {
+			e.push_stack(location.file(), location.line(), "traced");
+			return e;
+		});
+	}
 }
 
 /// Represent an object that can be thrown and can register the stack informations
 /// when beeing propagetd accross the call stack
-pub trait Throwable {
-	/// Push stack trace information
-	fn push_stack(&mut self, file: &'static str, line: u32, expr: &'static str);
+pub trait Throwable: AsAny + AsThrowable {
+	/// Push stack trace information. The default implementation pushes it with `push_entry`
+	fn push_stack(&mut self, file: &'static str, line: u32, expr: &'static str) {
+		self.push_entry(StackEntry{file: Cow::Borrowed(file), line: line, expr: Cow::Borrowed(expr), repeat: 1});
+	}
+	
+	/// Push a stack trace entry whose file or expression may be owned, like formatted labels or
+	/// deserialized entries
+	fn push_entry(&mut self, entry: StackEntry);
 	
-	/// Get the stack trace
-	fn get_stack_trace(&self) -> &Vec<StackEntry>;
+	/// Get the stack trace. Implementations storing their entries differently, like in a ring
+	/// buffer, implement `frames` instead, and keep the default implementation returning an
+	/// empty stack trace
+	fn get_stack_trace(&self) -> &StackTrace {
+		return &EMPTY_STACK_TRACE;
+	}
 	
 	/// Return the message explaining what caused the `Throwable` to be raised
 	fn get_message(&self) -> &str;
@@ -31,154 +330,2123 @@ pub trait Throwable {
 	/// Get the `Throwable` cause (if any) that caused this `Throwable` to be thrown
 	fn get_cause(&self) -> Option<&Throwable>;
 	
-	/// Print the stack trace to stdout. Code should instead call the `print_stack_trace!` macro
+	/// Set the `Throwable` that caused this one to be thrown, replacing the current cause.
+	/// The default implementation ignores it
+	#[allow(unused_variables)]
+	fn set_cause(&mut self, cause: Box<Throwable>) {}
+	
+	/// Record a value giving context about the failure, like the values of local variables.
+	/// The default implementation ignores it
+	#[allow(unused_variables)]
+	fn add_context(&mut self, key: &str, value: String) {}
+	
+	/// Get the context values recorded with `add_context`, as key and value pairs
+	fn get_context(&self) -> &[(String, String)] {
+		return &[];
+	}
+	
+	/// Record the calling location as a stack trace entry, for code propagating the `Throwable`
+	/// without the macros of this crate
+	/// 
+	/// ```
+	/// # #[macro_use] extern crate exceptions;
+	/// # use exceptions::prelude::*;
+	/// # fn parse(text: &str) -> Result<u32, Exception> { throw!(format!("Invalid number {}", text)); }
+	/// # fn load(text: &str) -> Result<u32, Exception> {
+	/// let config = parse(&text).map_err(|mut e| { e.record_here(); e })?;
+	/// # return Ok(config);
+	/// # }
+	/// # assert_eq!(load("x").unwrap_err().frame_count(), 2);
+	/// ```
+	#[track_caller]
+	fn record_here(&mut self) {
+		self.record_here_as("record_here");
+	}
+	
+	/// Record the calling location as a stack trace entry having `expr` as expression
+	#[track_caller]
+	fn record_here_as(&mut self, expr: &'static str) {
+		let location = panic::Location::caller();
+		self.push_stack(location.file(), location.line(), expr);
+	}
+	
+	/// Record the calling location as a stack trace entry, and return the `Throwable`
+	/// 
+	/// ```
+	/// # use exceptions::prelude::*;
+	/// # fn parse(text: &str) -> Result<usize, Exception> {
+	/// # if text.is_empty() {
+	/// return Err(Exception::new("Empty configuration".to_string()).traced());
+	/// # }
+	/// # return Ok(text.len());
+	/// # }
+	/// # assert_eq!(parse("").unwrap_err().frame_count(), 1);
+	/// ```
+	#[track_caller]
+	fn traced(mut self) -> Self where Self: Sized {
+		self.record_here_as("traced");
+		return self;
+	}
+	
+	/// Record a boundary in the stack trace, like the `Throwable` crossing from a thread or an
+	/// asynchronous task into another one. Entries recorded afterwards are outside of it
+	fn push_boundary(&mut self, label: &str) {
+		self.push_entry(StackEntry{file: Cow::Borrowed(""), line: 0, expr: Cow::Owned(label.to_string()), repeat: 1});
+	}
+	
+	/// Record a boundary for the current thread, before handing the `Throwable` over to another one
+	/// 
+	/// ```
+	/// # use exceptions::prelude::*;
+	/// # fn process() -> Result<(), Exception> { return Err(Exception::new("Cannot process".to_string())); }
+	/// let result = process().map_err(|mut e| { e.push_thread_boundary(); e });
+	/// # assert!(result.unwrap_err().top_frame().unwrap().is_boundary());
+	/// ```
+	fn push_thread_boundary(&mut self) {
+		let thread = std::thread::current();
+		self.push_boundary(&format!("thread '{}'", thread.name().unwrap_or("<unnamed>")));
+	}
+	
+	/// Get the kind of this `Throwable`, as a hierarchical identifier whose levels are separated
+	/// by dots, like `"io.timeout"`. The default implementation returns the empty kind, which is
+	/// the root of the hierarchy
+	fn kind(&self) -> &str {
+		return "";
+	}
+	
+	/// Check if the kind of this `Throwable` is `kind`, or one of its descendants. For example a
+	/// `Throwable` of kind `"io.timeout"` is an instance of `"io"`, but not of `"io.time"`
+	fn is_instance_of(&self, kind: &str) -> bool {
+		let own = self.kind();
+		return kind.is_empty() || own == kind || (own.starts_with(kind) && own[kind.len()..].starts_with('.'));
+	}
+	
+	/// Get the key grouping this `Throwable` with similar ones, to aggregate or deduplicate them
+	fn grouping_key(&self) -> grouping::GroupingKey {
+		return grouping::GroupingKey::of(self);
+	}
+	
+	/// Get a stable identifier of the logical error this `Throwable` is, for monitoring systems
+	/// grouping its occurrences across releases. See `grouping::fingerprint`
+	fn fingerprint(&self) -> String {
+		return grouping::fingerprint(self);
+	}
+	
+	/// Get an iterator over the stack trace entries, in the order they are printed, from the
+	/// outermost to the innermost one. Other methods access the entries through it. The default
+	/// implementation iterates over `get_stack_trace`
+	fn frames(&self) -> Frames<'_> {
+		return self.get_stack_trace().iter();
+	}
+	
+	/// Get the number of entries in the stack trace
+	fn frame_count(&self) -> usize {
+		return self.frames().len();
+	}
+	
+	/// Get the numbers of stack trace entries which were dropped, so that the rendered traces
+	/// show where entries are missing. The default implementation returns zeros
+	fn dropped_frames(&self) -> DroppedFrames {
+		return DroppedFrames::default();
+	}
+	
+	/// Record where the `Throwable` was handled, like by an arm of `catch!`, for post-mortem
+	/// analysis. The default implementation does nothing
+	#[allow(unused_variables)]
+	fn set_handled_at(&mut self, file: &'static str, line: u32, expr: &'static str) {}
+	
+	/// Get where the `Throwable` was handled, if it's recorded. The default implementation
+	/// returns `None`
+	fn handled_at(&self) -> Option<&StackEntry> {
+		return None;
+	}
+	
+	/// Mark the `Throwable` as thrown, telling if it wasn't yet, so that the context labels are
+	/// recorded and the `metrics` hook is notified only once. The default implementation
+	/// considers it not thrown until it has a stack trace entry, which may never come if capture
+	/// filters drop them. Implementations whose `push_entry` records nothing should override it
+	/// with a flag, as each rethrow would otherwise count as a new throw
+	fn mark_thrown(&mut self) -> bool {
+		return self.frame_count() == 0;
+	}
+	
+	/// Get the innermost stack trace entry, which is the closest to where the `Throwable` was raised
+	fn top_frame(&self) -> Option<&StackEntry> {
+		return self.frames().next_back();
+	}
+	
+	/// Find the first stack trace entry matching the predicate
+	fn find_frame<P>(&self, mut predicate: P) -> Option<&StackEntry> where Self: Sized, P: FnMut(&StackEntry) -> bool {
+		return self.frames().find(|s| predicate(s));
+	}
+	
+	/// Check if the `Throwable` went through the given file
+	fn contains_file(&self, path: &str) -> bool {
+		return self.frames().any(|s| s.file == path);
+	}
+	
+	/// Get an iterator over the causes of this `Throwable`, from the outermost to the innermost one
+	fn causes(&self) -> Causes<'_> {
+		return Causes{next: self.get_cause()};
+	}
+	
+	/// Get the innermost `Throwable` of the cause chain, or `self` if there is no cause
+	fn root_cause(&self) -> &Throwable {
+		return self.causes().last().unwrap_or(self.as_throwable());
+	}
+	
+	/// Find the first cause of type `T` in the cause chain
+	fn find_cause<T: Throwable>(&self) -> Option<&T> where Self: Sized {
+		return self.causes().filter_map(|c| c.downcast_ref::<T>()).next();
+	}
+	
+	/// Check if there is a cause of type `T` in the cause chain
+	fn has_cause<T: Throwable>(&self) -> bool where Self: Sized {
+		return self.find_cause::<T>().is_some();
+	}
+	
+	/// Check if the message of the `Throwable` or of one of its causes matches `pattern`, which
+	/// is a substring, or a `testing::Regex` with the `regex` feature
+	fn message_matches<P: testing::MessagePattern>(&self, pattern: P) -> bool where Self: Sized {
+		return Some(self as &Throwable).into_iter().chain(self.causes()).any(|th| pattern.matches(th.get_message()));
+	}
+	
+	/// Get the error this `Throwable` was created from, if it's kept. The default implementation
+	/// returns `None`
+	fn source_error(&self) -> Option<&(error::Error + 'static)> {
+		return None;
+	}
+	
+	/// Find an error of type `E` among the errors the `Throwable` and its causes were created
+	/// from, and their `source()` chains
+	/// 
+	/// ```
+	/// # use std::io;
+	/// # use exceptions::prelude::*;
+	/// # let th = Exception::from_error(io::Error::from(io::ErrorKind::Interrupted));
+	/// # let mut retry = false;
+	/// if let Some(e) = th.source_downcast_ref::<io::Error>() {
+	///     retry = e.kind() == io::ErrorKind::Interrupted;
+	/// }
+	/// # assert!(retry);
+	/// ```
+	fn source_downcast_ref<E: error::Error+'static>(&self) -> Option<&E> where Self: Sized {
+		let levels = Some(self as &Throwable).into_iter().chain(self.causes());
+		for level in levels {
+			let mut error = level.source_error();
+			while let Some(e) = error {
+				if let Some(e) = e.downcast_ref::<E>() {
+					return Some(e);
+				}
+				error = e.source();
+			}
+		}
+		return None;
+	}
+	
+	/// Get `self` as `Any`. Unlike `AsAny::as_any`, calling it on a reference to a `Throwable`
+	/// gets the referenced `Throwable`, not the reference
+	fn as_any_throwable(&self) -> &Any {
+		return self.as_any();
+	}
+	
+	/// Create the `Throwable` thrown by `throw!` for a string literal. The default implementation
+	/// converts it with `IntoThrowable`, `Exception` keeps it without allocating
+	#[doc(hidden)]
+	fn from_static_message(message: &'static str) -> Self where Self: Sized, &'static str: IntoThrowable<Self> {
+		return message.into_throwable();
+	}
+	
+	/// Print the stack trace to stdout. Code should instead call the `print_stack_trace!` macro.
+	/// Long entries are shortened to fit in the terminal width, if `COLUMNS` is set
 	#[allow(unused_must_use)] // Ignore if writing to stderr fails
 	fn print_stack_trace(&self) {
-		stdout().flush(); // Flush stdout to prevent mixes of stoud and stderr
+		self.try_print_stack_trace();
+	}
+	
+	/// Print the stack trace like `print_stack_trace`, returning the error if writing to stderr
+	/// fails
+	fn try_print_stack_trace(&self) -> io::Result<()> {
+		// Flush stdout to prevent mixes of stoud and stderr. Only failures of stderr are returned
+		let _ = stdout().flush();
 		let mut err = stderr();
-		writeln!(err, "{}", self.get_message());
-		for s in self.get_stack_trace() {
-			writeln!(err, "\tat {} [{}:{}]", s.expr, s.file, s.line); 
+		format::Formatter::configured().detect_width().write(self, &mut err)?;
+		return err.flush();
+	}
+	
+	/// Write the stack trace to `out`, followed by the ones of the causes, like
+	/// `print_stack_trace` does without fitting entries in the terminal width
+	fn write_stack_trace(&self, out: &mut Write) -> io::Result<()> {
+		return format::Formatter::configured().write(self, out);
+	}
+}
+
+impl Throwable {
+	/// Check if the concrete type of this `Throwable` is `T`. Boxed `Throwable`s are looked through
+	pub fn is<T: Throwable>(&self) -> bool {
+		return self.downcast_ref::<T>().is_some();
+	}
+	
+	/// Downcast this `Throwable` to its concrete type `T`. Boxed `Throwable`s are looked through
+	pub fn downcast_ref<T: Throwable>(&self) -> Option<&T> {
+		return downcast_throwable(self.as_any());
+	}
+}
+
+/// Record the active context labels on `th` and call the throw hook of the `metrics` module,
+/// the first time `th` is thrown or propagated, as told by `Throwable::mark_thrown`. Code should
+/// instead call the macros
+#[doc(hidden)]
+pub fn thrown<T: Throwable>(th: &mut T) {
+	if th.mark_thrown() {
+		context::attach(th);
+		metrics::notify(th);
+	}
+}
+
+/// Literal thrown by `throw!`. String literals are converted with
+/// `Throwable::from_static_message` by `StaticLiteral`, other literals with `IntoThrowable` by
+/// `IntoLiteral`, the method of the former being found first
+#[doc(hidden)]
+pub struct Literal<V>(pub V);
+
+#[doc(hidden)]
+pub trait StaticLiteral {
+	fn literal_throwable<T: Throwable>(&self) -> T where &'static str: IntoThrowable<T>;
+}
+
+impl StaticLiteral for &Literal<&'static str> {
+	fn literal_throwable<T: Throwable>(&self) -> T where &'static str: IntoThrowable<T> {
+		return T::from_static_message(self.0);
+	}
+}
+
+#[doc(hidden)]
+pub trait IntoLiteral<V> {
+	fn literal_throwable<T: Throwable>(&self) -> T where V: IntoThrowable<T>;
+}
+
+impl <V: Copy> IntoLiteral<V> for Literal<V> {
+	fn literal_throwable<T: Throwable>(&self) -> T where V: IntoThrowable<T> {
+		return self.0.into_throwable();
+	}
+}
+
+/// Run `handler` on `th`, then return it as an error. Used by `on_error!`, so that the type
+/// of `th` is inferred from the return type before checking the handler
+#[doc(hidden)]
+pub fn on_error_tap<T, E: Throwable, F: FnOnce(&E)>(th: E, handler: F) -> Result<T, E> {
+	handler(&th);
+	return Err(th);
+}
+
+/// Run `fallback` on `th`, returning its value. Used by `try_or_else!`, so that the type of
+/// `th` is inferred before checking the fallback
+#[doc(hidden)]
+pub fn try_or_else_tap<T, E: Throwable, F: FnOnce(E) -> T>(th: E, fallback: F) -> T {
+	return fallback(th);
+}
+
+/// Get `th`, or else its first cause, as a `T`. Used by the `as T e => handler` arms of `catch!`
+#[doc(hidden)]
+pub fn catch_downcast<T: Throwable, E: Throwable>(th: &E) -> Option<&T> {
+	return (th as &Throwable).downcast_ref::<T>().or_else(|| th.find_cause::<T>());
+}
+
+/// Get the name of the variant of the enum `E` that `th`, or else its first cause, was converted
+/// from. Used by `catch_kinds!`
+#[doc(hidden)]
+pub fn catch_variant<E: ?Sized, T: Throwable>(th: &T) -> Option<&'static str> {
+	return Some(th as &Throwable).into_iter().chain(th.causes()).filter_map(|c| c.downcast_ref::<Exception>()).filter_map(|e| e.variant_of::<E>()).next();
+}
+
+/// Map the traced error `th` with `map`, and trace the result as a new error caused by `th`.
+/// Used by `map_throwable!`
+#[doc(hidden)]
+pub fn map_throwable_tap<T, E, R, N, F>(th: E, map: F, file: &'static str, line: u32, expr: &'static str) -> Result<T, N>
+		where E: Throwable, R: IntoThrowable<N>, N: Throwable, F: FnOnce(&E) -> R {
+	let mut mapped = map(&th).into_throwable();
+	if mapped.get_cause().is_none() {
+		mapped.set_cause(Box::new(th));
+	}
+	mapped.push_stack(file, line, expr);
+	return Err(mapped);
+}
+
+/// Push a stack trace entry whose expression is the formatted `label`. Used by `with_context!`
+#[doc(hidden)]
+pub fn push_labeled_frame<T: Throwable+?Sized>(th: &mut T, file: &'static str, line: u32, label: fmt::Arguments) {
+	th.push_entry(StackEntry{file: Cow::Borrowed(file), line: line, expr: Cow::Owned(label.to_string()), repeat: 1});
+}
+
+/// Downcast `any` to `T`, looking through boxed `Throwable`s
+#[doc(hidden)]
+pub fn downcast_throwable<T: Throwable>(any: &Any) -> Option<&T> {
+	if let Some(th) = any.downcast_ref::<T>() {
+		return Some(th);
+	}
+	if let Some(boxed) = any.downcast_ref::<Box<Throwable>>() {
+		return downcast_throwable((**boxed).as_any());
+	}
+	return None;
+}
+
+/// Write the stack trace of `th`, followed by the ones of its causes
+fn write_stack_trace<T: Throwable+?Sized>(th: &T, out: &mut Write) -> io::Result<()> {
+	return format::Formatter::new().write(th, out);
+}
+
+/// Iterator over the cause chain of a `Throwable`
+pub struct Causes<'a> {
+	next: Option<&'a Throwable>
+}
+
+impl <'a> Iterator for Causes<'a> {
+	type Item = &'a Throwable;
+	
+	fn next(&mut self) -> Option<&'a Throwable> {
+		let current = self.next.take();
+		if let Some(cause) = current {
+			self.next = cause.get_cause();
+		}
+		return current;
+	}
+}
+
+/// Iterator over the entries of a stack trace, from the outermost to the innermost one
+#[derive(Clone)]
+pub struct Frames<'a> {
+	front: slice::Iter<'a, StackEntry>,
+	back: slice::Iter<'a, StackEntry>
+}
+
+impl <'a> Frames<'a> {
+	/// Iterate over `entries`, given from the outermost to the innermost one
+	pub fn new(entries: &'a [StackEntry]) -> Frames<'a> {
+		return Frames{front: entries.iter(), back: [].iter()};
+	}
+	
+	/// Iterate over `front`, then `back`, like the two parts of a ring buffer given by
+	/// `VecDeque::as_slices`
+	pub fn from_slices(front: &'a [StackEntry], back: &'a [StackEntry]) -> Frames<'a> {
+		return Frames{front: front.iter(), back: back.iter()};
+	}
+}
+
+impl <'a> Iterator for Frames<'a> {
+	type Item = &'a StackEntry;
+	
+	fn next(&mut self) -> Option<&'a StackEntry> {
+		return self.front.next().or_else(|| self.back.next());
+	}
+	
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let len = self.front.len() + self.back.len();
+		return (len, Some(len));
+	}
+}
+
+impl <'a> DoubleEndedIterator for Frames<'a> {
+	fn next_back(&mut self) -> Option<&'a StackEntry> {
+		return self.back.next_back().or_else(|| self.front.next_back());
+	}
+}
+
+impl <'a> ExactSizeIterator for Frames<'a> {}
+
+impl <T: Throwable+?Sized> Throwable for Box<T> {
+	fn push_stack(&mut self, file: &'static str, line: u32, expr: &'static str) {
+		(**self).push_stack(file, line, expr);
+	}
+	
+	fn push_entry(&mut self, entry: StackEntry) {
+		(**self).push_entry(entry);
+	}
+	
+	fn get_stack_trace(&self) -> &StackTrace {
+		return (**self).get_stack_trace();
+	}
+	
+	fn frames(&self) -> Frames<'_> {
+		return (**self).frames();
+	}
+	
+	fn get_message(&self) -> &str {
+		return (**self).get_message();
+	}
+	
+	fn get_cause(&self) -> Option<&Throwable> {
+		return (**self).get_cause();
+	}
+	
+	fn set_cause(&mut self, cause: Box<Throwable>) {
+		(**self).set_cause(cause);
+	}
+	
+	fn kind(&self) -> &str {
+		return (**self).kind();
+	}
+	
+	fn add_context(&mut self, key: &str, value: String) {
+		(**self).add_context(key, value);
+	}
+	
+	fn get_context(&self) -> &[(String, String)] {
+		return (**self).get_context();
+	}
+	
+	fn source_error(&self) -> Option<&(error::Error + 'static)> {
+		return (**self).source_error();
+	}
+	
+	fn dropped_frames(&self) -> DroppedFrames {
+		return (**self).dropped_frames();
+	}
+	
+	fn set_handled_at(&mut self, file: &'static str, line: u32, expr: &'static str) {
+		(**self).set_handled_at(file, line, expr);
+	}
+	
+	fn handled_at(&self) -> Option<&StackEntry> {
+		return (**self).handled_at();
+	}
+	
+	fn mark_thrown(&mut self) -> bool {
+		return (**self).mark_thrown();
+	}
+}
+
+/// Trait implented by types that can be converted
+/// into a type implementing `Throwable`
+pub trait IntoThrowable<T: Throwable> {
+	/// Convert `self` into the Throwable `T`
+	fn into_throwable(self) -> T;
+}
+
+impl <T: Throwable> IntoThrowable<T> for T {
+	fn into_throwable(self) -> T {
+		return self;
+	}
+}
+
+/// Trait implemented by types whose conversion into a type implementing `Throwable` can fail,
+/// like serialized exceptions. It's implemented for any conversion implementing `TryFrom`
+pub trait TryIntoThrowable<T: Throwable> {
+	/// Convert `self` into the Throwable `T`, or get an `Exception` explaining why it can't be
+	fn try_into_throwable(self) -> Result<T, Exception>;
+}
+
+impl <V, T> TryIntoThrowable<T> for V where T: Throwable + TryFrom<V>, T::Error: IntoThrowable<Exception> {
+	fn try_into_throwable(self) -> Result<T, Exception> {
+		return T::try_from(self).map_err(|e| e.into_throwable());
+	}
+}
+
+/// Infallible conversions never give an error
+impl IntoThrowable<Exception> for convert::Infallible {
+	fn into_throwable(self) -> Exception {
+		match self {}
+	}
+}
+
+/// Wrapper making any value convertible with `TryIntoThrowable` into an `Exception`, so that it
+/// can be thrown. When the conversion fails, the thrown `Exception` is a fallback of kind
+/// `exceptions.conversion`, caused by the conversion failure
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # struct Response { body: Vec<u8> }
+/// # fn fail(response: Response) -> Result<(), Exception> {
+/// throw!(Fallible(&response.body[..]));
+/// # }
+/// # assert!(fail(Response{body: b"Not found".to_vec()}).unwrap_err().is_instance_of("exceptions.conversion"));
+/// ```
+pub struct Fallible<V: TryIntoThrowable<Exception>>(pub V);
+
+impl <V: TryIntoThrowable<Exception>> IntoThrowable<Exception> for Fallible<V> {
+	fn into_throwable(self) -> Exception {
+		return match self.0.try_into_throwable() {
+			Ok(e) => e,
+			Err(failure) => {
+				let mut e = Exception::from_static("Cannot convert into an exception").caused_by(failure);
+				e.set_kind("exceptions.conversion");
+				e
+			}
+		};
+	}
+}
+
+/// Decode an exception encoded with `binary::encode`
+impl <'a> TryFrom<&'a [u8]> for Exception {
+	type Error = Exception;
+	
+	fn try_from(bytes: &'a [u8]) -> Result<Exception, Exception> {
+		return binary::decode(bytes);
+	}
+}
+
+/// Result of a function which can throw, like `fn load() -> Throws<Config>`
+pub type Throws<T, E = Exception> = Result<T, E>;
+
+/// A `Throwable` of any type, boxed
+pub type BoxedThrowable = Box<Throwable>;
+
+pub struct Exception {
+	message: Cow<'static, str>,
+	stack: StackTrace,
+	cause: Option<Box<Throwable>>,
+	max_frames: Option<usize>,
+	dropped_frames: DroppedFrames,
+	error: Option<Box<error::Error + Send + Sync>>,
+	http_status: Option<u16>,
+	context: Vec<(String, String)>,
+	kind: Option<String>,
+	handled_at: Option<StackEntry>,
+	variant: Option<(&'static str, &'static str)>,
+	thrown: bool
+}
+
+impl Exception {
+	/// Create an `Exception` with `message` and an empty stack trace. `new_here!` also records
+	/// the calling location
+	pub fn new(message: String) -> Exception {
+		return Exception::with_message(Cow::Owned(message));
+	}
+	
+	/// Create an `Exception` whose message is a static string. It doesn't allocate until stack
+	/// trace entries or context values are recorded. `throw!` uses it for string literals
+	pub fn from_static(message: &'static str) -> Exception {
+		return Exception::with_message(Cow::Borrowed(message));
+	}
+	
+	fn with_message(message: Cow<'static, str>) -> Exception {
+		return Exception{message: message, stack: StackTrace::from(pool::stack()), cause: None, max_frames: None, dropped_frames: DroppedFrames::default(), error: None, http_status: None, context: Vec::new(), kind: None, handled_at: None, variant: None, thrown: false};
+	}
+	
+	/// Create an `Exception` with a copy of `message`, reusing a buffer if a `pool::ThrowScope`
+	/// is entered
+	pub fn pooled(message: &str) -> Exception {
+		return Exception::new(pool::message(message));
+	}
+	
+	/// Create an `Exception` with a message built from format arguments, recording the calling
+	/// location as its first stack trace entry. The `exception!` macro is a shorthand for it
+	#[track_caller]
+	pub fn format(args: fmt::Arguments) -> Exception {
+		let location = panic::Location::caller();
+		let mut message = pool::message("");
+		let _ = fmt::Write::write_fmt(&mut message, args);
+		let mut e = Exception::new(message);
+		e.push_stack(location.file(), location.line(), "Exception::format");
+		return e;
+	}
+	
+	pub fn new_with_cause<T: Throwable+'static>(message: String, cause: T) -> Exception {
+		//FIXME: Take Box<T> or Box<Throwable> as cause argument
+		let mut e = Exception::new(message);
+		e.cause = Some(Box::new(cause));
+		return e;
+	}
+	
+	/// Set the cause of this `Exception`, replacing the current one, and return it. A
+	/// `Box<Throwable>` cause is kept as is, instead of being boxed again
+	/// 
+	/// ```
+	/// # #[macro_use] extern crate exceptions;
+	/// # use exceptions::prelude::*;
+	/// # fn load() -> Result<(), Exception> {
+	/// # let e = Exception::new("Cannot read".to_string());
+	/// throw!(Exception::new("Cannot load the configuration".to_string()).caused_by(e));
+	/// # }
+	/// # assert_eq!(load().unwrap_err().get_cause().unwrap().get_message(), "Cannot read");
+	/// ```
+	pub fn caused_by<T: Throwable+'static>(mut self, cause: T) -> Exception {
+		let mut cause = Some(cause);
+		let boxed = match (&mut cause as &mut Any).downcast_mut::<Option<Box<Throwable>>>() {
+			Some(boxed) => boxed.take(),
+			None => None
+		};
+		self.cause = match boxed {
+			Some(boxed) => Some(boxed),
+			None => cause.map(|c| Box::new(c) as Box<Throwable>)
+		};
+		return self;
+	}
+	
+	/// Create an `Exception` from `error`, keeping it so it can be retrieved with `error()`.
+	/// Each level of its `source()` chain becomes a cause having only a message
+	pub fn from_error<E: error::Error+Send+Sync+'static>(error: E) -> Exception {
+		let mut e = exception_from_error_chain(&error);
+		e.error = Some(Box::new(error));
+		return e;
+	}
+	
+	/// Get the error this `Exception` was created from with `from_error`, if any
+	pub fn error(&self) -> Option<&(error::Error+Send+Sync+'static)> {
+		return self.error.as_deref();
+	}
+	
+	/// Bound the number of stack trace entries retained to `max`. When exceeded, the outermost
+	/// and innermost entries are kept, and the entries in the middle are dropped
+	pub fn set_max_frames(&mut self, max: usize) {
+		self.max_frames = Some(max);
+		self.trim_frames();
+	}
+	
+	/// Set the kind returned by `kind()`, like `"io.timeout"`
+	pub fn set_kind(&mut self, kind: &str) {
+		self.kind = Some(kind.to_string());
+	}
+	
+	/// Record that the `Exception` was converted from the variant `variant` of the enum named
+	/// `ty`. Used by `#[derive(IntoThrowable)]`
+	#[doc(hidden)]
+	pub fn set_variant(&mut self, ty: &'static str, variant: &'static str) {
+		self.variant = Some((ty, variant));
+	}
+	
+	/// Get the name of the variant of the enum `E` the `Exception` was converted from with
+	/// `#[derive(IntoThrowable)]`, if it was
+	pub fn variant_of<E: ?Sized>(&self) -> Option<&'static str> {
+		return match self.variant {
+			Some((ty, variant)) if ty == any::type_name::<E>() => Some(variant),
+			_ => None
+		};
+	}
+	
+	/// Get an iterator over mutable references to the stack trace entries, from the outermost
+	/// to the innermost one, for example to rewrite their paths
+	pub fn frames_mut(&mut self) -> slice::IterMut<'_, StackEntry> {
+		return self.stack.iter_mut();
+	}
+	
+	/// Keep only the stack trace entries for which `predicate` returns `true`, for example to
+	/// strip the entries of a framework before reporting the `Exception`. The removed entries
+	/// aren't counted by `dropped_frames`, but the position of the dropped ones is kept
+	/// 
+	/// ```
+	/// # use exceptions::prelude::*;
+	/// # let mut e = Exception::new("Cannot connect".to_string());
+	/// # e.push_stack("/vendor/client/src/lib.rs", 12, "connect()");
+	/// # e.push_stack("src/main.rs", 4, "run()");
+	/// e.retain_frames(|f| !f.file.contains("/vendor/"));
+	/// # assert_eq!(e.frame_count(), 1);
+	/// ```
+	pub fn retain_frames<P: FnMut(&StackEntry) -> bool>(&mut self, mut predicate: P) {
+		let middle_at = self.dropped_frames.middle_at;
+		let mut index = 0;
+		let mut removed = 0;
+		self.stack.retain(|f| {
+			let keep = predicate(f);
+			if !keep && index < middle_at {
+				removed += 1;
+			}
+			index += 1;
+			return keep;
+		});
+		self.shift_middle(removed);
+	}
+	
+	/// Keep only the `len` innermost stack trace entries, which are the closest to where the
+	/// `Exception` was raised. The other ones are counted by `dropped_frames`
+	pub fn truncate_frames(&mut self, len: usize) {
+		let dropped = self.stack.len().saturating_sub(len);
+		self.dropped_frames.outermost += dropped;
+		self.shift_middle(dropped);
+		self.stack.truncate(len);
+	}
+	
+	fn trim_frames(&mut self) {
+		if let Some(max) = self.max_frames {
+			while self.stack.len() > max {
+				let index = max / 2;
+				self.stack.entries.remove(index);
+				if self.dropped_frames.middle == 0 {
+					self.dropped_frames.middle_at = index;
+				} else if index < self.dropped_frames.middle_at {
+					self.dropped_frames.middle_at -= 1;
+				}
+				self.dropped_frames.middle += 1;
+			}
+		}
+	}
+	
+	/// Move the middle dropped entries after `removed` kept entries before them were removed.
+	/// Once no kept entry is before them, they're counted with the outermost ones
+	fn shift_middle(&mut self, removed: usize) {
+		let dropped = &mut self.dropped_frames;
+		if removed < dropped.middle_at {
+			dropped.middle_at -= removed;
+		} else {
+			dropped.outermost += dropped.middle;
+			dropped.middle = 0;
+			dropped.middle_at = 0;
+		}
+	}
+}
+
+impl Throwable for Exception {
+	/// The entry is dropped if it's not kept by the capture filters of the `filter` module
+	fn push_entry(&mut self, entry: StackEntry) {
+		if !filter::captured(&entry) {
+			return;
+		}
+		let len = self.stack.len();
+		self.stack.push(entry);
+		// A new outermost entry moves the kept ones, and the middle dropped entries with them
+		if self.stack.len() > len && self.dropped_frames.middle > 0 {
+			self.dropped_frames.middle_at += 1;
+		}
+		self.trim_frames();
+	}
+	
+	fn from_static_message(message: &'static str) -> Exception {
+		return Exception::from_static(message);
+	}
+	
+	fn get_stack_trace(&self) -> &StackTrace {
+		return &self.stack;
+	}
+	
+	fn get_message(&self) -> &str {
+		return &self.message;
+	}
+	
+	fn get_cause(&self) -> Option<&Throwable> {
+//		return match self.cause {
+//			Some(ref c) => Some(c),
+//			None => None
+//		};
+		if self.cause.is_some() {
+			return Some(self.cause.as_ref().unwrap().deref())
+		}
+		return None;
+	}
+	
+	fn set_cause(&mut self, cause: Box<Throwable>) {
+		self.cause = Some(cause);
+	}
+	
+	/// The kind is the one set with `set_kind`, or else derived from the `io::Error` the
+	/// `Exception` was created from, if any
+	fn kind(&self) -> &str {
+		if let Some(ref kind) = self.kind {
+			return kind;
+		}
+		return match self.error().and_then(|e| e.downcast_ref::<io::Error>()).map(|e| e.kind()) {
+			Some(io::ErrorKind::NotFound) => "io.not_found",
+			Some(io::ErrorKind::PermissionDenied) => "io.permission_denied",
+			Some(io::ErrorKind::AlreadyExists) => "io.already_exists",
+			Some(io::ErrorKind::InvalidInput) | Some(io::ErrorKind::InvalidData) => "io.invalid",
+			Some(io::ErrorKind::TimedOut) => "io.timeout",
+			Some(_) => "io",
+			None => ""
+		};
+	}
+	
+	fn add_context(&mut self, key: &str, value: String) {
+		self.context.push((key.to_string(), value));
+	}
+	
+	fn get_context(&self) -> &[(String, String)] {
+		return &self.context;
+	}
+	
+	fn source_error(&self) -> Option<&(error::Error + 'static)> {
+		return self.error().map(|e| e as &(error::Error + 'static));
+	}
+	
+	fn dropped_frames(&self) -> DroppedFrames {
+		return self.dropped_frames;
+	}
+	
+	fn set_handled_at(&mut self, file: &'static str, line: u32, expr: &'static str) {
+		self.handled_at = Some(StackEntry{file: Cow::Borrowed(file), line: line, expr: Cow::Borrowed(expr), repeat: 1});
+	}
+	
+	fn handled_at(&self) -> Option<&StackEntry> {
+		return self.handled_at.as_ref();
+	}
+	
+	fn mark_thrown(&mut self) -> bool {
+		let first = !self.thrown;
+		self.thrown = true;
+		return first;
+	}
+}
+
+/// Give the buffers back to the pool, if a `pool::ThrowScope` is entered
+impl Drop for Exception {
+	fn drop(&mut self) {
+		if let Cow::Owned(message) = mem::replace(&mut self.message, Cow::Borrowed("")) {
+			pool::recycle(message, mem::replace(&mut self.stack, StackTrace::new()).entries);
+		} else {
+			pool::recycle(String::new(), mem::replace(&mut self.stack, StackTrace::new()).entries);
+		}
+	}
+}
+
+impl fmt::Display for Exception {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return f.write_str(&self.message);
+	}
+}
+
+/// Render the whole stack trace, like `print_stack_trace()`
+impl fmt::Debug for Exception {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut trace = Vec::new();
+		let _ = write_stack_trace(self, &mut trace);
+		return f.write_str(String::from_utf8_lossy(&trace).trim_end());
+	}
+}
+
+impl <'r> IntoThrowable<Exception> for &'r str {
+	fn into_throwable(self) -> Exception {
+		return Exception::new(self.to_string());
+	}
+}
+
+impl IntoThrowable<Exception> for String {
+	fn into_throwable(self) -> Exception {
+		return Exception::new(self);
+	}
+}
+
+impl IntoThrowable<Exception> for fmt::Error {
+	fn into_throwable(self) -> Exception {
+		return Exception::new("Formatting Exception".to_string());
+	}
+}
+
+/// Implement `IntoThrowable<$target>` for each of the given types, using their `ToString`
+/// implementation as message. `$target` must have a `new(String)` constructor, like `Exception`.
+/// Because of coherence rules, either `$target` or the converted types must be defined in the
+/// crate calling the macro
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # pub struct MyException { message: String }
+/// # impl MyException {
+/// #     pub fn new(message: String) -> MyException { return MyException{message: message}; }
+/// # }
+/// # impl Throwable for MyException {
+/// #     fn push_entry(&mut self, _entry: StackEntry) {}
+/// #     fn get_message(&self) -> &str { return &self.message; }
+/// #     fn get_cause(&self) -> Option<&Throwable> { return None; }
+/// # }
+/// impl_into_throwable!(MyException; std::num::ParseIntError, std::net::AddrParseError);
+/// # let e: MyException = "x".parse::<u16>().unwrap_err().into_throwable();
+/// # assert_eq!(e.get_message(), "invalid digit found in string");
+/// ```
+#[macro_export]
+macro_rules! impl_into_throwable {
+	($target:ty; $($t:ty),+ $(,)*) => (
+		$(
+			impl $crate::IntoThrowable<$target> for $t {
+				fn into_throwable(self) -> $target {
+					return <$target>::new(self.to_string());
+				}
+			}
+		)+
+	)
+}
+
+impl IntoThrowable<Exception> for io::Error {
+	fn into_throwable(self) -> Exception {
+		return Exception::from_error(self);
+	}
+}
+
+/// The error is kept, so it can be retrieved with `Exception::error()`, and each level of its
+/// `source()` chain becomes a cause having only a message
+impl IntoThrowable<Exception> for Box<error::Error + Send + Sync> {
+	fn into_throwable(self) -> Exception {
+		let mut e = exception_from_error_chain(&*self);
+		e.error = Some(self);
+		return e;
+	}
+}
+
+/// Each level of the `source()` chain of the error becomes a cause having only a message
+impl IntoThrowable<Exception> for Box<error::Error> {
+	fn into_throwable(self) -> Exception {
+		return exception_from_error_chain(&*self);
+	}
+}
+
+/// A lock is poisoned when a thread panicked while holding it. The guard is dropped
+impl <T> IntoThrowable<Exception> for sync::PoisonError<T> {
+	fn into_throwable(self) -> Exception {
+		return sync_exception("Lock poisoned: a thread panicked while holding it", "sync.poisoned");
+	}
+}
+
+impl <T> IntoThrowable<Exception> for sync::TryLockError<T> {
+	fn into_throwable(self) -> Exception {
+		return match self {
+			sync::TryLockError::Poisoned(e) => e.into_throwable(),
+			sync::TryLockError::WouldBlock => sync_exception("Lock already held", "sync.would_block")
+		};
+	}
+}
+
+impl IntoThrowable<Exception> for mpsc::RecvError {
+	fn into_throwable(self) -> Exception {
+		return sync_exception("Channel disconnected: all the senders were dropped", "sync.disconnected");
+	}
+}
+
+impl IntoThrowable<Exception> for mpsc::TryRecvError {
+	fn into_throwable(self) -> Exception {
+		return match self {
+			mpsc::TryRecvError::Empty => sync_exception("Channel empty", "sync.empty"),
+			mpsc::TryRecvError::Disconnected => mpsc::RecvError.into_throwable()
+		};
+	}
+}
+
+/// The value which couldn't be sent is dropped
+impl <T> IntoThrowable<Exception> for mpsc::SendError<T> {
+	fn into_throwable(self) -> Exception {
+		return sync_exception("Channel disconnected: the receiver was dropped", "sync.disconnected");
+	}
+}
+
+/// Create an `Exception` describing a synchronization failure
+fn sync_exception(message: &'static str, kind: &str) -> Exception {
+	let mut e = Exception::from_static(message);
+	e.set_kind(kind);
+	return e;
+}
+
+impl_into_throwable!(Exception;
+	num::ParseIntError,
+	num::ParseFloatError,
+	net::AddrParseError,
+	str::ParseBoolError,
+	str::Utf8Error,
+	string::FromUtf8Error
+);
+
+/// Wrapper making any `std::error::Error` convertible into an `Exception`. A blanket
+/// implementation over `E: Error` would conflict with other `IntoThrowable` implementations
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn parse(s: &str) -> Result<u16, Exception> {
+/// let port: u16 = try!(s.parse().map_err(StdError));
+/// # return Ok(port);
+/// # }
+/// # assert_eq!(parse("x").unwrap_err().get_message(), "invalid digit found in string");
+/// ```
+pub struct StdError<E: error::Error>(pub E);
+
+/// Each level of the `source()` chain of the error becomes a cause having only a message
+impl <E: error::Error> IntoThrowable<Exception> for StdError<E> {
+	fn into_throwable(self) -> Exception {
+		return exception_from_error_chain(&self.0);
+	}
+}
+
+/// `Throwable` storing an error of any type next to its stack trace, so that the original value
+/// can be retrieved afterwards. The `source()` chain of the error becomes the cause chain
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::{error, fmt};
+/// # use exceptions::prelude::*;
+/// # #[derive(Debug)]
+/// # enum ClientError { Refused }
+/// # impl fmt::Display for ClientError {
+/// #     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { return write!(f, "Connection refused"); }
+/// # }
+/// # impl error::Error for ClientError {}
+/// # struct Client;
+/// # impl Client {
+/// #     fn connect(_url: &str) -> Result<Client, ClientError> { return Err(ClientError::Refused); }
+/// # }
+/// # let url = "http://localhost:8080";
+/// # let mut retried = false;
+/// # let mut retry = || retried = true;
+/// fn connect(url: &str) -> Result<Client, WrappedError<ClientError>> {
+///     let client = try!(Client::connect(url).map_err(WrappedError::new));
+///     return Ok(client);
+/// }
+/// 
+/// if let Err(e) = connect(url) {
+///     if let ClientError::Refused = *e.inner() {
+///         retry();
+///     }
+/// }
+/// # assert!(retried);
+/// ```
+pub struct WrappedError<E: error::Error+'static> {
+	error: E,
+	exception: Exception
+}
+
+impl <E: error::Error+'static> WrappedError<E> {
+	/// Wrap `error`, with an empty stack trace
+	pub fn new(error: E) -> WrappedError<E> {
+		let exception = exception_from_error_chain(&error);
+		return WrappedError{error: error, exception: exception};
+	}
+	
+	/// Get the original error
+	pub fn inner(&self) -> &E {
+		return &self.error;
+	}
+	
+	/// Get the original error, dropping the stack trace
+	pub fn into_inner(self) -> E {
+		return self.error;
+	}
+}
+
+impl <E: error::Error+'static> Throwable for WrappedError<E> {
+	fn push_entry(&mut self, entry: StackEntry) {
+		self.exception.push_entry(entry);
+	}
+	
+	fn get_stack_trace(&self) -> &StackTrace {
+		return self.exception.get_stack_trace();
+	}
+	
+	fn get_message(&self) -> &str {
+		return self.exception.get_message();
+	}
+	
+	fn get_cause(&self) -> Option<&Throwable> {
+		return self.exception.get_cause();
+	}
+	
+	fn set_cause(&mut self, cause: Box<Throwable>) {
+		self.exception.set_cause(cause);
+	}
+	
+	fn kind(&self) -> &str {
+		return self.exception.kind();
+	}
+	
+	fn add_context(&mut self, key: &str, value: String) {
+		self.exception.add_context(key, value);
+	}
+	
+	fn get_context(&self) -> &[(String, String)] {
+		return self.exception.get_context();
+	}
+	
+	fn source_error(&self) -> Option<&(error::Error + 'static)> {
+		return Some(&self.error);
+	}
+	
+	fn dropped_frames(&self) -> DroppedFrames {
+		return self.exception.dropped_frames();
+	}
+	
+	fn set_handled_at(&mut self, file: &'static str, line: u32, expr: &'static str) {
+		self.exception.set_handled_at(file, line, expr);
+	}
+	
+	fn handled_at(&self) -> Option<&StackEntry> {
+		return self.exception.handled_at();
+	}
+	
+	fn mark_thrown(&mut self) -> bool {
+		return self.exception.mark_thrown();
+	}
+}
+
+impl <E: error::Error+'static> fmt::Display for WrappedError<E> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return f.write_str(self.get_message());
+	}
+}
+
+/// Render the original error, followed by the whole stack trace
+impl <E: error::Error+'static> fmt::Debug for WrappedError<E> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return write!(f, "{:?}: {:?}", self.error, self.exception);
+	}
+}
+
+/// Description of an `Exception` which can be built at compile time, to be defined once as a
+/// `static` or `const` and thrown many times. Each `throw!` of it creates a new `Exception`,
+/// with its own stack trace, borrowing the static message
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// static NOT_FOUND: StaticException = static_exception!("Not found").kind("io.not_found").status(404);
+/// 
+/// # fn find() -> Result<(), Exception> {
+/// throw!(NOT_FOUND);
+/// # }
+/// # assert_eq!(find().unwrap_err().http_status(), 404);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct StaticException {
+	message: &'static str,
+	kind: Option<&'static str>,
+	status: Option<u16>
+}
+
+impl StaticException {
+	pub const fn new(message: &'static str) -> StaticException {
+		return StaticException{message: message, kind: None, status: None};
+	}
+	
+	/// Set the kind of the thrown `Exception`s
+	pub const fn kind(mut self, kind: &'static str) -> StaticException {
+		self.kind = Some(kind);
+		return self;
+	}
+	
+	/// Set the HTTP status code of the thrown `Exception`s
+	pub const fn status(mut self, status: u16) -> StaticException {
+		self.status = Some(status);
+		return self;
+	}
+	
+	pub fn get_message(&self) -> &'static str {
+		return self.message;
+	}
+}
+
+impl IntoThrowable<Exception> for StaticException {
+	fn into_throwable(self) -> Exception {
+		let mut e = Exception::from_static(self.message);
+		if let Some(kind) = self.kind {
+			e.set_kind(kind);
+		}
+		if let Some(status) = self.status {
+			e.set_http_status(status);
+		}
+		return e;
+	}
+}
+
+/// Error type for `main`, holding any `Throwable`. Returned as the error of `main`, its full
+/// stack trace is printed on stderr and the process exits with a failure code. Returned by
+/// `main` directly, it always fails, like with `Err`. `Throwable`s are converted into it by the
+/// `?` operator
+/// 
+/// ```
+/// # extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn load_config() -> Result<u16, Exception> { return Ok(8080); }
+/// fn main() -> Result<(), exceptions::Report> {
+///     let config = load_config()?;
+/// #   assert_eq!(config, 8080);
+///     return Ok(());
+/// }
+/// ```
+pub struct Report {
+	inner: Box<Throwable>
+}
+
+impl Report {
+	/// Get the reported `Throwable`
+	pub fn throwable(&self) -> &Throwable {
+		return &*self.inner;
+	}
+	
+	/// Get the reported `Throwable`, boxed
+	pub fn into_inner(self) -> Box<Throwable> {
+		return self.inner;
+	}
+}
+
+impl <T: Throwable> From<T> for Report {
+	fn from(th: T) -> Report {
+		return Report{inner: Box::new(th)};
+	}
+}
+
+/// Render the full stack trace, like `print_stack_trace()`
+impl fmt::Debug for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let trace = format::Formatter::configured().detect_width().render(&*self.inner);
+		return f.write_str(trace.trim_end());
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		return f.write_str(self.inner.get_message());
+	}
+}
+
+impl process::Termination for Report {
+	fn report(self) -> process::ExitCode {
+		let _ = writeln!(stderr(), "Error: {:?}", self);
+		return process::ExitCode::FAILURE;
+	}
+}
+
+/// Build an `Exception` from a chain of messages, from the outermost to the innermost one.
+/// Each message after the first one becomes the cause of the previous one
+fn exception_from_messages(mut messages: Vec<String>) -> Exception {
+	let mut e = Exception::new(messages.pop().unwrap_or_default());
+	while let Some(message) = messages.pop() {
+		e = Exception::new_with_cause(message, e);
+	}
+	return e;
+}
+
+/// Run `f`, converting its error into a traced `Exception`. A panic of `f` is caught and thrown
+/// as an `Exception` too, having the panic message. The calling location is recorded in the
+/// stack trace, making it a boundary for calling code which isn't trusted
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # struct Plugin;
+/// # impl Plugin {
+/// #     fn run(&self, input: &str) -> Result<String, Exception> { panic!("Unsupported input {}", input); }
+/// # }
+/// # fn convert(plugin: &Plugin, input: &str) -> Result<String, Exception> {
+/// let output = try!(exceptions::throwing(|| plugin.run(input)));
+/// # return Ok(output);
+/// # }
+/// # assert!(convert(&Plugin, "x").unwrap_err().get_message().contains("Unsupported input x"));
+/// ```
+#[track_caller]
+pub fn throwing<T, E, F>(f: F) -> Result<T, Exception> where E: IntoThrowable<Exception>, F: FnOnce() -> Result<T, E> {
+	let location = panic::Location::caller();
+	let mut e = match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+		Ok(Ok(value)) => return Ok(value),
+		Ok(Err(e)) => e.into_throwable(),
+		Err(payload) => Exception::new(format!("Panic: {}", panic_message(&*payload)))
+	};
+	thrown(&mut e);
+	e.push_stack(location.file(), location.line(), "throwing");
+	return Err(e);
+}
+
+/// Get the message of a panic from its payload
+fn panic_message(payload: &Any) -> String {
+	return match payload.downcast_ref::<&str>() {
+		Some(message) => message.to_string(),
+		None => payload.downcast_ref::<String>().cloned().unwrap_or_else(|| "Box<Any>".to_string())
+	};
+}
+
+/// Build an `Exception` from `error`, each level of its `source()` chain becoming a cause
+/// having only a message
+#[doc(hidden)]
+pub fn exception_from_error_chain(error: &error::Error) -> Exception {
+	let mut messages = vec!(error.to_string());
+	let mut source = error.source();
+	while let Some(s) = source {
+		messages.push(s.to_string());
+		source = s.source();
+	}
+	return exception_from_messages(messages);
+}
+
+/// Convert the `Exception` into an `io::Error` of kind `Other`, having the rendered
+/// stack trace as message
+impl From<Exception> for io::Error {
+	fn from(e: Exception) -> io::Error {
+		let mut trace = Vec::new();
+		let _ = write_stack_trace(&e, &mut trace);
+		let trace = String::from_utf8_lossy(&trace);
+		return io::Error::other(trace.trim_end().to_string());
+	}
+}
+
+//impl <E: ToString> IntoThrowable<Exception> for E {
+//	fn into_throwable(self) -> Exception {
+//		return Exception::new(self.to_string());
+//	}
+//}
+
+/// Declare a `Throwable` companion struct for an error type, for example an enum deriving
+/// `thiserror::Error`. The companion stores the original error next to the stack trace, and the
+/// error type becomes convertible into it so it can be used with the `try!` and `throw!` macros.
+/// The `source()` chain of the error becomes the cause chain of the companion
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::{error, fmt, io};
+/// # use exceptions::prelude::*;
+/// #[derive(Debug)]
+/// pub enum StorageError {
+///     NotFound(String),
+///     Io(io::Error)
+/// }
+/// # impl fmt::Display for StorageError {
+/// #     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+/// #         return match *self {
+/// #             StorageError::NotFound(ref key) => write!(f, "key {} not found", key),
+/// #             StorageError::Io(..) => write!(f, "i/o failure")
+/// #         };
+/// #     }
+/// # }
+/// # impl error::Error for StorageError {}
+/// 
+/// throwable_error!(pub struct StorageException(StorageError););
+/// 
+/// fn load(key: &str) -> Result<Vec<u8>, StorageException> {
+///     throw!(StorageError::NotFound(key.to_string()));
+/// }
+/// # assert_eq!(load("users").unwrap_err().get_message(), "key users not found");
+/// ```
+#[macro_export]
+macro_rules! throwable_error {
+	($(#[$attr:meta])* $vis:vis struct $name:ident($error:ty);) => (
+		$(#[$attr])*
+		$vis struct $name {
+			error: $error,
+			exception: $crate::Exception
+		}
+		
+		impl $name {
+			/// Get the original error
+			#[allow(dead_code)]
+			pub fn error(&self) -> &$error {
+				return &self.error;
+			}
+			
+			/// Get the original error, dropping the stack trace
+			#[allow(dead_code)]
+			pub fn into_error(self) -> $error {
+				return self.error;
+			}
+		}
+		
+		impl $crate::Throwable for $name {
+			fn push_entry(&mut self, entry: $crate::StackEntry) {
+				self.exception.push_entry(entry);
+			}
+			
+			fn get_stack_trace(&self) -> &$crate::StackTrace {
+				return self.exception.get_stack_trace();
+			}
+			
+			fn get_message(&self) -> &str {
+				return self.exception.get_message();
+			}
+			
+			fn get_cause(&self) -> Option<&$crate::Throwable> {
+				return self.exception.get_cause();
+			}
+			
+			fn set_cause(&mut self, cause: Box<$crate::Throwable>) {
+				self.exception.set_cause(cause);
+			}
+			
+			fn kind(&self) -> &str {
+				return self.exception.kind();
+			}
+			
+			fn add_context(&mut self, key: &str, value: String) {
+				self.exception.add_context(key, value);
+			}
+			
+			fn get_context(&self) -> &[(String, String)] {
+				return self.exception.get_context();
+			}
+			
+			fn source_error(&self) -> Option<&(::std::error::Error + 'static)> {
+				return Some(&self.error);
+			}
+			
+			fn dropped_frames(&self) -> $crate::DroppedFrames {
+				return self.exception.dropped_frames();
+			}
+			
+			fn set_handled_at(&mut self, file: &'static str, line: u32, expr: &'static str) {
+				self.exception.set_handled_at(file, line, expr);
+			}
+			
+			fn handled_at(&self) -> Option<&$crate::StackEntry> {
+				return self.exception.handled_at();
+			}
+			
+			fn mark_thrown(&mut self) -> bool {
+				return self.exception.mark_thrown();
+			}
+		}
+		
+		impl $crate::IntoThrowable<$name> for $error {
+			fn into_throwable(self) -> $name {
+				let exception = $crate::exception_from_error_chain(&self);
+				return $name{error: self, exception: exception};
+			}
+		}
+	)
+}
+
+/// Record the name and version of the calling crate, so they are stated at the end of every
+/// rendered trace. The revision is read from the `GIT_HASH` environment variable at compile
+/// time, which can be set by a build script, or given explicitly
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// fn main() {
+///     build_info!();
+///     // or build_info!(env!("MY_REVISION"));
+/// #   assert!(exceptions::build::build_info().is_some());
+/// }
+/// ```
+#[macro_export]
+macro_rules! build_info {
+	() => (
+		$crate::build::set_build_info($crate::build::BuildInfo{
+			name: env!("CARGO_PKG_NAME"),
+			version: env!("CARGO_PKG_VERSION"),
+			revision: option_env!("GIT_HASH")
+		})
+	);
+	($revision:expr) => (
+		$crate::build::set_build_info($crate::build::BuildInfo{
+			name: env!("CARGO_PKG_NAME"),
+			version: env!("CARGO_PKG_VERSION"),
+			revision: Some($revision)
+		})
+	)
+}
+
+/// Create an `Exception` with a formatted message, recording the calling location as its
+/// first stack trace entry
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn open(path: &str) -> Result<(), Exception> {
+/// throw!(exception!("Cannot open {}", path));
+/// # }
+/// # assert_eq!(open("config.toml").unwrap_err().get_message(), "Cannot open config.toml");
+/// ```
+#[macro_export]
+macro_rules! exception {
+	($($arg:tt)+) => (
+		{
+			let mut e = $crate::Exception::new(format!($($arg)+));
+			$crate::Throwable::push_stack(&mut e, file!(), line!(), stringify!(exception!($($arg)+)));
+			e
+		}
+	)
+}
+
+/// Create an `Exception` with `message`, recording the calling location as its first stack
+/// trace entry, for exceptions returned without `throw!`, which would record an entry too
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn user(id: u32) -> Result<String, Exception> {
+/// return Err(new_here!(format!("Unknown user {}", id)));
+/// # }
+/// # assert_eq!(user(7).unwrap_err().frame_count(), 1);
+/// ```
+#[macro_export]
+macro_rules! new_here {
+	($message:expr) => (
+		{
+			let mut e = $crate::Exception::new($message);
+			$crate::Throwable::push_stack(&mut e, file!(), line!(), "new_here!");
+			e
+		}
+	)
+}
+
+/// Define a `StaticException` with the given message, usable in `static` and `const` items
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// static TIMEOUT: StaticException = static_exception!("Timed out");
+/// # fn wait() -> Result<(), Exception> { throw!(TIMEOUT); }
+/// # assert_eq!(wait().unwrap_err().get_message(), "Timed out");
+/// ```
+#[macro_export]
+macro_rules! static_exception {
+	($message:expr) => (
+		$crate::StaticException::new($message)
+	)
+}
+
+#[macro_export]
+macro_rules! try {
+	($expr:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				return std::result::Result::Err(th);
+			},
+		}
+	);
+	($($expr:expr); *;) => ($($crate::try!($expr)); *;)
+}
+
+/// Evaluate each `Result` in order, returning a tuple of their values, or the first traced
+/// error like `try!`
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn load_config() -> Result<u16, Exception> { return Ok(8080); }
+/// # fn connect(addr: &str) -> Result<String, Exception> { throw!(format!("Cannot connect to {}", addr)); }
+/// # fn start(addr: &str) -> Result<(u16, String), Exception> {
+/// let (config, socket) = try_all!(load_config(), connect(addr));
+/// # return Ok((config, socket));
+/// # }
+/// # assert_eq!(start("localhost").unwrap_err().get_message(), "Cannot connect to localhost");
+/// ```
+#[macro_export]
+macro_rules! try_all {
+	($($expr:expr),+ $(,)*) => (
+		($($crate::try!($expr),)+)
+	)
+}
+
+/// Evaluate statements in a closure, returning a `Result<T, Exception>` of the value of the
+/// last expression, so that `try!` and `throw!` leave the block instead of the calling function.
+/// As in a closure, `return` leaves the block too, and `break` and `continue` can't reach the
+/// loops around it
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::fs;
+/// # use exceptions::prelude::*;
+/// # fn parse_settings(text: &str) -> Result<usize, Exception> { return Ok(text.len()); }
+/// # let path = "missing.toml";
+/// let settings = try_block! {
+///     let text = try!(fs::read_to_string(path));
+///     try!(parse_settings(&text))
+/// };
+/// # assert!(settings.is_err());
+/// ```
+#[macro_export]
+macro_rules! try_block {
+	($($body:tt)*) => (
+		{
+			// The body may end by throwing, leaving `Ok` unreachable
+			#[allow(unreachable_code)]
+			let result = (|| -> std::result::Result<_, $crate::Exception> {
+				return std::result::Result::Ok({ $($body)* });
+			})();
+			result
+		}
+	)
+}
+
+/// Like `try!`, but run `$handler` with a reference to the traced error before returning it,
+/// for side effects like logging
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # mod metrics {
+/// #     pub fn increment(_counter: &str) {}
+/// # }
+/// # fn fetch(url: &str) -> Result<String, Exception> { throw!(format!("Cannot fetch {}", url)); }
+/// # fn load(url: &str) -> Result<String, Exception> {
+/// let data = on_error!(fetch(url), |e| metrics::increment("fetch_failures"));
+/// # return Ok(data);
+/// # }
+/// # assert!(load("/users").is_err());
+/// ```
+#[macro_export]
+macro_rules! on_error {
+	($expr:expr, $handler:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				return $crate::on_error_tap(th, $handler);
+			},
+		}
+	)
+}
+
+/// Like `try!`, but convert the traced error with `$map`, which is given a reference to it.
+/// The result is traced from the call site, with the original error as cause unless `$map`
+/// already set one
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn read_file(path: &str) -> Result<String, Exception> { throw!(format!("Cannot read {}", path)); }
+/// # fn load(path: &str) -> Result<String, Exception> {
+/// let config = map_throwable!(read_file(path), |e| Exception::new("Cannot load the configuration".to_string()));
+/// # return Ok(config);
+/// # }
+/// # assert_eq!(load("config.toml").unwrap_err().get_cause().unwrap().get_message(), "Cannot read config.toml");
+/// ```
+#[macro_export]
+macro_rules! map_throwable {
+	($expr:expr, $map:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				return $crate::map_throwable_tap(th, $map, file!(), line!(), stringify!(map_throwable!($expr)));
+			},
+		}
+	)
+}
+
+/// Evaluate a `Result`, returning its value, or `$default` if it failed. With `; report`, the
+/// traced error is sent to the reporters before being discarded
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # #[derive(Default)]
+/// # struct Theme { name: String }
+/// # fn load_theme(path: &str) -> Result<Theme, Exception> { throw!(format!("Cannot read {}", path)); }
+/// # let path = "theme.toml";
+/// let theme = try_or!(load_theme(path), Theme::default(); report);
+/// # assert_eq!(theme.name, "");
+/// ```
+#[macro_export]
+macro_rules! try_or {
+	($expr:expr, $default:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(..) => $default
+		}
+	);
+	($expr:expr, $default:expr; report) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				th.set_handled_at(file!(), line!(), "try_or!");
+				$crate::report::report(&th);
+				$default
+			},
+		}
+	)
+}
+
+/// Evaluate a `Result`, returning its value, or else the value of `$fallback`, which is given
+/// the traced error
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # struct Db;
+/// # impl Db {
+/// #     fn load_user(&self, id: u32) -> Result<String, Exception> { throw!(format!("Unknown user {}", id)); }
+/// # }
+/// # struct Cache;
+/// # impl Cache {
+/// #     fn user(&self, id: u32) -> String { return format!("user {}", id); }
+/// # }
+/// # let (db, cache, id) = (Db, Cache, 7);
+/// let user = try_or_else!(db.load_user(id), |e| { e.print_stack_trace(); cache.user(id) });
+/// # assert_eq!(user, "user 7");
+/// ```
+#[macro_export]
+macro_rules! try_or_else {
+	($expr:expr, $fallback:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				th.set_handled_at(file!(), line!(), "try_or_else!");
+				$crate::try_or_else_tap(th, $fallback)
+			},
 		}
-		if let Some(cause) = self.get_cause() {
-			write!(err, "Caused by: ");
-			cause.print_stack_trace();
-		} 
-		err.flush();
-	}
+	)
 }
 
-impl <T: Throwable+?Sized> Throwable for Box<T> {
-	fn push_stack(&mut self, file: &'static str, line: u32, expr: &'static str) {
-		(**self).push_stack(file, line, expr);
-	}
-	
-	fn get_stack_trace(&self) -> &Vec<StackEntry> {
-		return (**self).get_stack_trace();
-	}
-	
-	fn get_message(&self) -> &str {
-		return (**self).get_message();
-	}
-	
-	fn get_cause(&self) -> Option<&Throwable> {
-		return (**self).get_cause();
-	}
+/// Evaluate a `Result`, returning its value. On failure, the stack trace of the traced error
+/// is written to the sink, then the calling function returns, or `$default` is evaluated if
+/// given. Handy in `main`, examples and tests, which can't propagate errors
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # struct Config;
+/// # impl Config {
+/// #     fn port(&self) -> Result<u16, Exception> { throw!("No port set"); }
+/// # }
+/// # fn load_config() -> Result<Config, Exception> { return Ok(Config); }
+/// fn main() {
+///     let config = unwrap_or_print!(load_config());
+///     let port = unwrap_or_print!(config.port(), 8080);
+/// #   assert_eq!(port, 8080);
+/// }
+/// ```
+#[macro_export]
+macro_rules! unwrap_or_print {
+	($expr:expr) => (
+		$crate::unwrap_or_print!($expr, return)
+	);
+	($expr:expr, $default:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				th.set_handled_at(file!(), line!(), "unwrap_or_print!");
+				$crate::sink::write_trace(&th);
+				$default
+			},
+		}
+	)
 }
 
-/// Trait implented by types that can be converted
-/// into a type implementing `Throwable`
-pub trait IntoThrowable<T: Throwable> {
-	/// Convert `self` into the Throwable `T`
-	fn into_throwable(self) -> T;
+/// Evaluate a `Result`, returning its value. On failure, the stack trace of the traced error is
+/// logged through the `log` crate, at the error level or the given one, and the enclosing loop
+/// continues with its next iteration. Enabled by the `log` feature
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # extern crate log;
+/// # use exceptions::prelude::*;
+/// # fn read_file(path: &str) -> Result<String, Exception> { return Ok(path.to_string()); }
+/// # fn parse(data: &str) -> Result<u32, Exception> { return data.parse::<u32>().map_err(|e| e.into_throwable()); }
+/// # let paths = vec!["12", "x"];
+/// # let mut records = Vec::new();
+/// for path in paths {
+///     let data = log_and_continue!(read_file(&path));
+///     let record = log_and_continue!(parse(&data), log::Level::Warn);
+/// #   records.push(record);
+/// }
+/// # assert_eq!(records, vec![12]);
+/// ```
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_and_continue {
+	($expr:expr) => (
+		$crate::log_and_continue!($expr, $crate::__LogLevel::Error)
+	);
+	($expr:expr, $level:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				th.set_handled_at(file!(), line!(), "log_and_continue!");
+				$crate::log_trace(&th, $level);
+				continue;
+			},
+		}
+	)
 }
 
-impl <T: Throwable> IntoThrowable<T> for T {
-	fn into_throwable(self) -> T {
-		return self;
-	}
+/// Like `try!`, but also record a stack trace entry labeled with the formatted message, telling
+/// what was being done when the error happened
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn read_config(_path: &str) -> Result<String, Exception> { throw!("Cannot read"); }
+/// # fn load(path: &str) -> Result<String, Exception> {
+/// let config = with_context!("loading config from {}", path => read_config(path));
+/// # return Ok(config);
+/// # }
+/// # assert!(load("config.toml").unwrap_err().find_frame(|f| f.expr == "loading config from config.toml").is_some());
+/// ```
+#[macro_export]
+macro_rules! with_context {
+	($($label:expr),+ => $expr:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				$crate::push_labeled_frame(&mut th, file!(), line!(), format_args!($($label),+));
+				return std::result::Result::Err(th);
+			},
+		}
+	)
 }
 
-pub struct Exception {
-	message: String,
-	stack: Vec<StackEntry>,
-	cause: Option<Box<Throwable>>
+#[macro_export]
+macro_rules! translate {
+	($expr:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				return std::result::Result::Err($crate::translate::translate(e, file!(), line!(), stringify!($expr)));
+			},
+		}
+	)
 }
 
-impl Exception {
-	pub fn new(message: String) -> Exception {
-		return Exception{message: message, stack: Vec::new(), cause: None};
-	}
-	
-	pub fn new_with_cause<T: Throwable+'static>(message: String, cause: T) -> Exception {
-		//FIXME: Take Box<T> or Box<Throwable> as cause argument
-		return Exception{message: message, stack: Vec::new(), cause: Some(Box::new(cause))};
-	}
+/// Throw the given value, converted into a `Throwable`. String literals are converted like
+/// other values, but thrown as `Exception`s without copying them. Context values can be given
+/// after the value, rendered with their `Display` implementation, or their `Debug` one if
+/// prefixed by `?`
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # #[derive(Debug)]
+/// # struct Options { retries: u32 }
+/// # fn connect(host: &str, port: u16, options: Options) -> Result<(), Exception> {
+/// throw!("Cannot connect"; host = host, port = port, options = ?options);
+/// # }
+/// # let e = connect("localhost", 8080, Options{retries: 3}).unwrap_err();
+/// # assert!(e.get_context().contains(&("options".to_string(), "Options { retries: 3 }".to_string())));
+/// ```
+#[macro_export]
+macro_rules! throw {
+	($message:literal) => (
+		{
+			#[allow(unused_imports)]
+			use $crate::{StaticLiteral, IntoLiteral};
+			let mut e = (&&$crate::Literal($message)).literal_throwable();
+			$crate::thrown(&mut e);
+			$crate::Throwable::push_stack(&mut e, file!(), line!(), stringify!(throw!($message)));
+			return std::result::Result::Err(e);
+		}
+	);
+	($expr:expr) => (
+		{
+			let mut e = $expr.into_throwable();
+			$crate::thrown(&mut e);
+			e.push_stack(file!(), line!(), stringify!(throw!($expr)));
+			return std::result::Result::Err(e);
+		}
+	);
+	($expr:expr; $($context:tt)+) => (
+		{
+			let mut e = $expr.into_throwable();
+			$crate::__exceptions_context!(e; $($context)+);
+			$crate::thrown(&mut e);
+			e.push_stack(file!(), line!(), stringify!(throw!($expr)));
+			return std::result::Result::Err(e);
+		}
+	)
 }
 
-impl Throwable for Exception {
-	fn push_stack(&mut self, file: &'static str, line: u32, expr: &'static str) {
-		self.stack.insert(0, StackEntry{file: file, line: line, expr: expr});
-	}
-	
-	fn get_stack_trace(&self) -> &Vec<StackEntry> {
-		return &self.stack;
-	}
-	
-	fn get_message(&self) -> &str {
-		return &self.message;
-	}
-	
-	fn get_cause(&self) -> Option<&Throwable> {
-//		return match self.cause {
-//			Some(ref c) => Some(c),
-//			None => None
-//		};
-		if self.cause.is_some() {
-			return Some(self.cause.as_ref().unwrap().deref())
+/// The `throw!` and `try!` macros, boxing the errors into `BoxedThrowable`. Used by the bodies
+/// of `#[throws]` functions declaring several exception types
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __throws_boxed {
+	(throw $expr:expr) => (
+		{
+			let mut e: $crate::BoxedThrowable = Box::new($expr);
+			$crate::thrown(&mut e);
+			$crate::Throwable::push_stack(&mut e, file!(), line!(), stringify!(throw!($expr)));
+			return std::result::Result::Err(e);
 		}
-		return None;
-	}
+	);
+	(throw $expr:expr; $($context:tt)+) => (
+		{
+			let mut e: $crate::BoxedThrowable = Box::new($expr);
+			$crate::__exceptions_context!(e; $($context)+);
+			$crate::thrown(&mut e);
+			$crate::Throwable::push_stack(&mut e, file!(), line!(), stringify!(throw!($expr)));
+			return std::result::Result::Err(e);
+		}
+	);
+	(propagate $expr:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th: $crate::BoxedThrowable = Box::new(e);
+				$crate::thrown(&mut th);
+				$crate::Throwable::push_stack(&mut th, file!(), line!(), stringify!($expr));
+				return std::result::Result::Err(th);
+			},
+		}
+	)
 }
 
-impl <'r> IntoThrowable<Exception> for &'r str {
-	fn into_throwable(self) -> Exception {
-		return Exception::new(self.to_string());
-	}
+/// The `try!` and `throw!` macros, recording the given line and expression. Used by the
+/// `try_spanned!` and `throw_spanned!` procedural macros
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exceptions_spanned {
+	(propagate $line:expr, $text:expr; $expr:expr) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), $line, $text);
+				return std::result::Result::Err(th);
+			},
+		}
+	);
+	(throw $line:expr, $text:expr; $expr:expr) => (
+		{
+			let mut e = $expr.into_throwable();
+			$crate::thrown(&mut e);
+			e.push_stack(file!(), $line, $text);
+			return std::result::Result::Err(e);
+		}
+	)
 }
 
-impl IntoThrowable<Exception> for String {
-	fn into_throwable(self) -> Exception {
-		return Exception::new(self);
-	}
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exceptions_context {
+	($e:ident;) => ();
+	($e:ident; $key:ident = ?$value:expr) => (
+		$e.add_context(stringify!($key), format!("{:?}", $value));
+	);
+	($e:ident; $key:ident = ?$value:expr, $($rest:tt)*) => (
+		$e.add_context(stringify!($key), format!("{:?}", $value));
+		$crate::__exceptions_context!($e; $($rest)*);
+	);
+	($e:ident; $key:ident = $value:expr) => (
+		$e.add_context(stringify!($key), format!("{}", $value));
+	);
+	($e:ident; $key:ident = $value:expr, $($rest:tt)*) => (
+		$e.add_context(stringify!($key), format!("{}", $value));
+		$crate::__exceptions_context!($e; $($rest)*);
+	)
 }
 
-impl IntoThrowable<Exception> for fmt::Error {
-	fn into_throwable(self) -> Exception {
-		return Exception::new("Formatting Exception".to_string());
-	}
+/// Check if a `Throwable` matches a pattern, without downcasting and comparing by hand.
+/// The pattern is a concrete `Throwable` type, or `_` for any type, optionally followed by
+/// fields to check: `message` must be a substring of its message, `kind` must be its kind or
+/// a parent of it, and `status` must be equal to its `HttpStatus` code
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # let mut e = Exception::new("Request timed out".to_string());
+/// # e.set_http_status(504);
+/// # let mut retried = false;
+/// # let mut retry = || retried = true;
+/// if matches_exception!(e, Exception { message: "timed out", status: 504 }) {
+///     retry();
+/// }
+/// assert!(matches_exception!(e, Exception));
+/// assert!(matches_exception!(e, _ { message: "Request" }));
+/// # assert!(retried);
+/// ```
+#[macro_export]
+macro_rules! matches_exception {
+	($e:expr, _ { .. }) => (true);
+	($e:expr, _ { $($field:ident: $value:expr),+ $(, ..)* }) => (
+		{
+			let th = &$e;
+			true $(&& $crate::__exceptions_matches_field!(th, $field, $value))+
+		}
+	);
+	($e:expr, $t:ty) => (
+		$crate::downcast_throwable::<$t>($e.as_any_throwable()).is_some()
+	);
+	($e:expr, $t:ty { .. }) => (
+		$crate::matches_exception!($e, $t)
+	);
+	($e:expr, $t:ty { $($field:ident: $value:expr),+ $(, ..)* }) => (
+		match $crate::downcast_throwable::<$t>($e.as_any_throwable()) {
+			std::option::Option::Some(th) => true $(&& $crate::__exceptions_matches_field!(th, $field, $value))+,
+			std::option::Option::None => false
+		}
+	)
 }
 
-impl IntoThrowable<Exception> for io::Error {
-	fn into_throwable(self) -> Exception {
-		return Exception::new(self.to_string());
-	}
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exceptions_matches_field {
+	($th:ident, message, $value:expr) => (
+		$th.get_message().contains($value)
+	);
+	($th:ident, kind, $value:expr) => (
+		$th.is_instance_of($value)
+	);
+	($th:ident, status, $value:expr) => (
+		$crate::HttpStatus::http_status($th) == $value
+	)
 }
 
-//impl <E: error::Error> IntoThrowable<Exception> for E {
-//	fn into_throwable(self) -> Exception {
-//		return Exception::new(self.description().to_string());
-//	}
-//}
-
-//impl <E: ToString> IntoThrowable<Exception> for E {
-//	fn into_throwable(self) -> Exception {
-//		return Exception::new(self.to_string());
-//	}
-//}
-
+/// Assert that evaluating a `Result` throws, and return the error. If an expected `Throwable`
+/// is given, the thrown one is compared to it with `testing::diff`, and the differences are
+/// reported on failure. Given `message: pattern` instead, the message of the thrown one or of
+/// one of its causes must match the pattern, as checked by `Throwable::message_matches`
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn parse(text: &str) -> Result<u32, Exception> {
+/// #     return text.parse::<u32>().map_err(|_| Exception::new("Invalid number".to_string()));
+/// # }
+/// let e = assert_throws!(parse("x"), Exception::new("Invalid number".to_string()));
+/// let e = assert_throws!(parse("x"), message: "Invalid");
+/// ```
 #[macro_export]
-macro_rules! try {
+macro_rules! assert_throws {
 	($expr:expr) => (
 		match $expr {
-			std::result::Result::Ok(e) => e,
+			std::result::Result::Ok(..) => panic!("assertion failed: `{}` did not throw", stringify!($expr)),
+			std::result::Result::Err(e) => e
+		}
+	);
+	($expr:expr, message: $pattern:expr) => (
+		match $expr {
+			std::result::Result::Ok(..) => panic!("assertion failed: `{}` did not throw", stringify!($expr)),
 			std::result::Result::Err(e) => {
-				let mut th = e.into_throwable();
-				th.push_stack(file!(), line!(), stringify!($expr));
-				return std::result::Result::Err(th);
-			},
+				if !e.message_matches($pattern) {
+					panic!("assertion failed: `{}` threw an exception not matching `{}`\n{}", stringify!($expr), stringify!($pattern), $crate::testing::normalize(&e));
+				}
+				e
+			}
 		}
 	);
-	($($expr:expr); *;) => ($(try!($expr)); *;)
+	($expr:expr, $expected:expr) => (
+		match $expr {
+			std::result::Result::Ok(..) => panic!("assertion failed: `{}` did not throw", stringify!($expr)),
+			std::result::Result::Err(e) => {
+				if let std::option::Option::Some(diff) = $crate::testing::diff(&$expected, &e) {
+					panic!("assertion failed: `{}` threw an unexpected exception\n{}", stringify!($expr), diff);
+				}
+				e
+			}
+		}
+	)
 }
 
+/// Assert that the trace of a `Throwable` matches the baseline stored in a file, whose path is
+/// relative to the directory of the manifest of the calling crate. See
+/// `testing::compare_baseline`
+/// 
+/// ```no_run
+/// # #[macro_use] extern crate exceptions;
+/// # use exceptions::prelude::*;
+/// # fn load(path: &str) -> Result<String, Exception> { throw!(format!("Cannot read {}", path)); }
+/// let e = assert_throws!(load("missing.toml"));
+/// assert_baseline!(e, "tests/baselines/missing_config.txt");
+/// ```
 #[macro_export]
-macro_rules! throw {
+macro_rules! assert_baseline {
+	($th:expr, $path:expr) => (
+		if let std::result::Result::Err(diff) = $crate::testing::compare_baseline(&$th, std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path)) {
+			panic!("assertion failed: {}", diff);
+		}
+	)
+}
+
+/// Send a `Throwable` to the reporters, after recording the call site in its stack trace
+#[macro_export]
+macro_rules! report {
 	($expr:expr) => (
 		{
-			let mut e = $expr.into_throwable();
-			e.push_stack(file!(), line!(), stringify!(throw!($expr)));
-			return std::result::Result::Err(e);
+			$expr.push_stack(file!(), line!(), stringify!(report!($expr)));
+			$crate::report::report(&$expr);
 		}
 	)
 }
@@ -193,6 +2461,58 @@ macro_rules! print_stack_trace {
 	)
 }
 
+/// Evaluate a `Result`, tracing its error without returning from the calling function.
+/// Given several statements, they are evaluated until one fails, and its error records
+/// the `statement` index (from 0) and `expression` text as context values.
+/// 
+/// Given arms after the expression, its value is returned on success, and its traced error
+/// is given to the first arm whose guard holds. Arms are `e if guard => handler`, `e => handler`
+/// or `_ e => handler`, the last two catching any error. An `as T e => handler` arm catches the
+/// error if it's a `T` or has a cause of type `T`, and binds a reference to it. The location of
+/// `catch!` is recorded as where the error was handled. When no arm catches it, the error is
+/// returned from the calling function like `try!`
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::{error, fmt, io};
+/// # use exceptions::prelude::*;
+/// # throwable_error! {
+/// #     #[derive(Debug)]
+/// #     pub struct IoException(io::Error);
+/// # }
+/// # #[derive(Debug)]
+/// # pub struct DbError;
+/// # impl fmt::Display for DbError {
+/// #     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { return write!(f, "Connection lost"); }
+/// # }
+/// # impl error::Error for DbError {}
+/// # throwable_error! {
+/// #     #[derive(Debug)]
+/// #     pub struct DbException(DbError);
+/// # }
+/// # fn fetch(url: &str) -> Result<String, Exception> { throw!(format!("timeout fetching {}", url)); }
+/// # fn retry(url: &str) -> String { return format!("retried {}", url); }
+/// # fn load_user(id: u32) -> Result<String, Exception> {
+/// #     let cause: IoException = io::Error::from(io::ErrorKind::NotFound).into_throwable();
+/// #     return Err(Exception::new_with_cause(format!("Cannot load user {}", id), cause));
+/// # }
+/// # struct Cache;
+/// # impl Cache {
+/// #     fn user(&self, id: u32) -> String { return format!("user {}", id); }
+/// # }
+/// # fn run(url: &str, id: u32, cache: Cache) -> Result<(String, String), Exception> {
+/// let data = catch! { fetch(url);
+///     e if e.get_message().contains("timeout") => retry(url),
+///     _ e => throw!(e)
+/// };
+/// let user = catch! { load_user(id);
+///     as IoException io => cache.user(id),
+///     as DbException db => throw!(db.error().to_string())
+/// };
+/// # return Ok((data, user));
+/// # }
+/// # assert_eq!(run("/users", 7, Cache).unwrap(), ("retried /users".to_string(), "user 7".to_string()));
+/// ```
 #[macro_export]
 macro_rules! catch {
 	($expr:expr) => (
@@ -200,26 +2520,172 @@ macro_rules! catch {
 			std::result::Result::Ok(e) => std::result::Result::Ok(e),
 			std::result::Result::Err(e) => {
 				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
 				th.push_stack(file!(), line!(), stringify!($expr));
 				std::result::Result::Err(th)
 			},
 		}
 	);
 	
+	($expr:expr; as $($arm:tt)+) => (
+		$crate::catch!(@arms $expr; as $($arm)+)
+	);
+	($expr:expr; _ $name:ident => $($arm:tt)+) => (
+		$crate::catch!(@arms $expr; _ $name => $($arm)+)
+	);
+	($expr:expr; $name:ident if $($arm:tt)+) => (
+		$crate::catch!(@arms $expr; $name if $($arm)+)
+	);
+	($expr:expr; $name:ident => $($arm:tt)+) => (
+		$crate::catch!(@arms $expr; $name => $($arm)+)
+	);
+	(@arms $expr:expr; $($arm:tt)+) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				$crate::__exceptions_catch_arms!(th; $($arm)+)
+			},
+		}
+	);
+	
 	($($expr:expr); *;) => (
 		{
 			let mut result;
+			#[allow(unused_assignments)]
+			let mut index = 0usize;
 			loop {
 				$(
-					result = catch!($expr);
-					match result {
-						std::result::Result::Err(..) => break,
-						_ => {}
+					result = $crate::catch!($expr);
+					if let std::result::Result::Err(ref mut e) = result {
+						e.add_context("statement", index.to_string());
+						e.add_context("expression", stringify!($expr).to_string());
+						break;
 					}
+					index += 1;
 				)*
 				break;
 			}
 			result
 		}
 	)
-}
\ No newline at end of file
+}
+
+/// Like `catch!`, but with an arm for each variant of an enum deriving `IntoThrowable`, which
+/// is given the traced error if it was converted from this variant. A missing variant is a
+/// compilation error, like in a `match`. Errors converted from other types are returned from
+/// the calling function like `try!`
+/// 
+/// ```
+/// # #[macro_use] extern crate exceptions;
+/// # use std::any;
+/// # use exceptions::prelude::*;
+/// # #[derive(Default)]
+/// # struct Config;
+/// # enum ConfigError { Read { path: String }, Invalid(u32) }
+/// # impl IntoThrowable<Exception> for ConfigError {
+/// #     fn into_throwable(self) -> Exception {
+/// #         let (message, variant) = match self {
+/// #             ConfigError::Read { path } => (format!("Cannot read {}", path), "Read"),
+/// #             ConfigError::Invalid(line) => (format!("Invalid value at line {}", line), "Invalid")
+/// #         };
+/// #         let mut e = Exception::new(message);
+/// #         e.set_variant(any::type_name::<ConfigError>(), variant);
+/// #         return e;
+/// #     }
+/// # }
+/// # fn load_config(path: &str) -> Result<Config, ConfigError> { return Err(ConfigError::Read{path: path.to_string()}); }
+/// # fn load(path: &str) -> Result<Config, Exception> {
+/// let config = catch_kinds! { load_config(path); as ConfigError e {
+///     Read => Config::default(),
+///     Invalid => throw!(e)
+/// }};
+/// # return Ok(config);
+/// # }
+/// # assert!(load("config.toml").is_ok());
+/// ```
+#[macro_export]
+macro_rules! catch_kinds {
+	($expr:expr; as $($ty:ident)::+ $name:ident { $($variant:ident => $handler:expr),+ $(,)* }) => (
+		$crate::catch_kinds!(@arms $expr; $($ty)::+; $name; $($variant => $handler),+)
+	);
+	(@arms $expr:expr; $ty:ty; $name:ident; $($variant:ident => $handler:expr),+) => (
+		match $expr {
+			std::result::Result::Ok(e) => e,
+			std::result::Result::Err(e) => {
+				let mut th = e.into_throwable();
+				$crate::thrown(&mut th);
+				th.push_stack(file!(), line!(), stringify!($expr));
+				{
+					// Never called, only checking that there is an arm for every variant
+					type Kinds = $ty;
+					let _ = |v: &Kinds| match *v {
+						$(Kinds::$variant { .. } => ()),+
+					};
+				}
+				let variant = $crate::catch_variant::<$ty, _>(&th);
+				$(
+					if variant == std::option::Option::Some(stringify!($variant)) {
+						th.set_handled_at(file!(), line!(), "catch_kinds!");
+						let $name = th;
+						$handler
+					} else
+				)+ {
+					return std::result::Result::Err(th);
+				}
+			},
+		}
+	)
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exceptions_catch_arms {
+	($th:ident;) => (
+		return std::result::Result::Err($th)
+	);
+	($th:ident; as $($ty:ident)::+ $name:ident => $handler:expr) => (
+		$crate::__exceptions_catch_arms!($th; as $($ty)::+ $name => $handler,)
+	);
+	($th:ident; as $($ty:ident)::+ $name:ident => $handler:expr, $($rest:tt)*) => (
+		if $crate::catch_downcast::<$($ty)::+, _>(&$th).is_some() {
+			$th.set_handled_at(file!(), line!(), "catch!");
+			let $name = $crate::catch_downcast::<$($ty)::+, _>(&$th).unwrap();
+			$handler
+		} else {
+			$crate::__exceptions_catch_arms!($th; $($rest)*)
+		}
+	);
+	($th:ident; _ $name:ident => $handler:expr $(,)*) => (
+		{
+			$th.set_handled_at(file!(), line!(), "catch!");
+			let $name = $th;
+			$handler
+		}
+	);
+	($th:ident; $name:ident => $handler:expr $(,)*) => (
+		{
+			$th.set_handled_at(file!(), line!(), "catch!");
+			let $name = $th;
+			$handler
+		}
+	);
+	($th:ident; $name:ident if $guard:expr => $handler:expr) => (
+		$crate::__exceptions_catch_arms!($th; $name if $guard => $handler,)
+	);
+	($th:ident; $name:ident if $guard:expr => $handler:expr, $($rest:tt)*) => (
+		{
+			let $name = $th;
+			if $guard {
+				let mut $name = $name;
+				$name.set_handled_at(file!(), line!(), "catch!");
+				$handler
+			} else {
+				let mut $th = $name;
+				$crate::__exceptions_catch_arms!($th; $($rest)*)
+			}
+		}
+	)
+}
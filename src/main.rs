@@ -1,5 +1,7 @@
+// `Result<T, Exception>` is how the crate returns failures
+#![allow(clippy::result_large_err)]
 #[macro_use] extern crate exceptions;
-use exceptions::*;
+use exceptions::prelude::*;
 
 fn test1() -> Result<(), Exception> {
 	println!("test1()");
@@ -0,0 +1,31 @@
+//! Instrumentation hook notified each time an exception is thrown, for example to count
+//! exceptions by type
+use std::sync::RwLock;
+use Throwable;
+
+type Hook = fn(&Throwable);
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Set the function called with each newly thrown `Throwable`, returning the previous one. It's
+/// called the first time a `Throwable` is thrown or propagated by the `throw!`, `try!`, `catch!`
+/// and `translate!` macros, `throwing`, the `?` operator of `nightly::Throws` and
+/// `subprocess::output`, but not when an exception is only created. Rethrowing or propagating it
+/// doesn't call the hook again, even if the capture filters of the `filter` module dropped its
+/// stack trace entries
+pub fn set_throw_hook(hook: fn(&Throwable)) -> Option<fn(&Throwable)> {
+	let mut current = HOOK.write().unwrap_or_else(|e| e.into_inner());
+	return current.replace(hook);
+}
+
+/// Remove the function set with `set_throw_hook`
+pub fn remove_throw_hook() {
+	*HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Call the hook set with `set_throw_hook`, if any
+pub(crate) fn notify(th: &Throwable) {
+	if let Some(hook) = *HOOK.read().unwrap_or_else(|e| e.into_inner()) {
+		hook(th);
+	}
+}
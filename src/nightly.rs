@@ -0,0 +1,90 @@
+//! Integration with the `?` operator, enabled by the `nightly` feature since it relies on the
+//! unstable `Try` trait. Functions returning a `Throws` record a stack trace entry each time an
+//! error is propagated with `?`, without needing the `try!` macro
+//! 
+//! ```
+//! # extern crate exceptions;
+//! # use std::fs;
+//! # use exceptions::prelude::*;
+//! use exceptions::nightly::Throws;
+//! # struct Config;
+//! # fn parse(_text: &str) -> Throws<Config> { return Throws::ok(Config); }
+//! 
+//! fn load(path: &str) -> Throws<Config> {
+//!     let text = fs::read_to_string(path)?;
+//!     Throws::ok(parse(&text)?)
+//! }
+//! # fn main() {
+//! # assert_eq!(load("missing.toml").into_result().err().unwrap().top_frame().unwrap().expr, "?");
+//! # }
+//! ```
+use std::convert::Infallible;
+use std::ops::{ControlFlow, FromResidual, Residual, Try};
+use std::panic;
+use {Throwable, IntoThrowable, Exception, thrown};
+
+/// Result of a function which can throw, recording where errors are propagated with `?`.
+/// The recorded expression is `?`, at the location of the operator
+pub struct Throws<T, E: Throwable = Exception>(pub Result<T, E>);
+
+impl <T, E: Throwable> Throws<T, E> {
+	/// Create a successful `Throws` holding `value`
+	pub fn ok(value: T) -> Throws<T, E> {
+		return Throws(Ok(value));
+	}
+	
+	/// Get the underlying `Result`
+	pub fn into_result(self) -> Result<T, E> {
+		return self.0;
+	}
+}
+
+impl <T, E: Throwable> From<Result<T, E>> for Throws<T, E> {
+	fn from(result: Result<T, E>) -> Throws<T, E> {
+		return Throws(result);
+	}
+}
+
+impl <T, E: Throwable> Try for Throws<T, E> {
+	type Output = T;
+	type Residual = Throws<Infallible, E>;
+	
+	fn from_output(value: T) -> Throws<T, E> {
+		return Throws(Ok(value));
+	}
+	
+	fn branch(self) -> ControlFlow<Throws<Infallible, E>, T> {
+		return match self.0 {
+			Ok(value) => ControlFlow::Continue(value),
+			Err(e) => ControlFlow::Break(Throws(Err(e)))
+		};
+	}
+}
+
+impl <T, E: Throwable> Residual<T> for Throws<Infallible, E> {
+	type TryType = Throws<T, E>;
+}
+
+impl <T, E: Throwable, F: Throwable+IntoThrowable<E>> FromResidual<Throws<Infallible, F>> for Throws<T, E> {
+	#[track_caller]
+	fn from_residual(residual: Throws<Infallible, F>) -> Throws<T, E> {
+		let Err(e) = residual.0;
+		return Throws(Err(traced(e, panic::Location::caller())));
+	}
+}
+
+/// Allow `?` on plain `Result`s, whose errors are converted into throwables
+impl <T, E: Throwable, F: IntoThrowable<E>> FromResidual<Result<Infallible, F>> for Throws<T, E> {
+	#[track_caller]
+	fn from_residual(residual: Result<Infallible, F>) -> Throws<T, E> {
+		let Err(e) = residual;
+		return Throws(Err(traced(e, panic::Location::caller())));
+	}
+}
+
+fn traced<E: Throwable, F: IntoThrowable<E>>(e: F, location: &'static panic::Location<'static>) -> E {
+	let mut th = e.into_throwable();
+	thrown(&mut th);
+	th.push_stack(location.file(), location.line(), "?");
+	return th;
+}
@@ -0,0 +1,100 @@
+//! Reuse of the buffers of exceptions, for hot error paths like parsers and network servers
+//! where the allocations made when building exceptions show up in profiles
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use StackEntry;
+
+/// Maximum number of buffers of each type kept for reuse, per thread
+const MAX_POOLED: usize = 64;
+
+struct Pool {
+	scopes: usize,
+	messages: Vec<String>,
+	stacks: Vec<Vec<StackEntry>>
+}
+
+thread_local!(static POOL: RefCell<Pool> = const { RefCell::new(Pool{scopes: 0, messages: Vec::new(), stacks: Vec::new()}) });
+
+/// Guard enabling the reuse of buffers on the current thread until dropped. While a scope is
+/// entered, dropped `Exception`s give their message and stack trace buffers back to a pool, and
+/// new ones take their buffers from it: `Exception::new` reuses stack trace buffers, and
+/// `Exception::pooled` and `Exception::format` reuse message buffers too. The pool is emptied
+/// when the outermost scope is left
+/// 
+/// ```
+/// # use exceptions::prelude::*;
+/// # use exceptions::pool::ThrowScope;
+/// # fn parse(line: &str) -> Result<u32, Exception> {
+/// #     return line.parse::<u32>().map_err(|_| Exception::pooled(line));
+/// # }
+/// # let lines = vec!["1", "x", "y"];
+/// # let mut errors = 0;
+/// let _scope = ThrowScope::enter();
+/// for line in lines {
+///     if let Err(e) = parse(line) {
+///         errors += 1;
+///     }
+/// }
+/// # assert_eq!(errors, 2);
+/// ```
+pub struct ThrowScope {
+	// The pool belongs to a thread
+	_not_send: PhantomData<*const ()>
+}
+
+impl ThrowScope {
+	/// Enable the reuse of buffers on the current thread
+	pub fn enter() -> ThrowScope {
+		POOL.with(|p| p.borrow_mut().scopes += 1);
+		return ThrowScope{_not_send: PhantomData};
+	}
+}
+
+impl Drop for ThrowScope {
+	fn drop(&mut self) {
+		let _ = POOL.try_with(|p| {
+			let mut pool = p.borrow_mut();
+			pool.scopes -= 1;
+			if pool.scopes == 0 {
+				pool.messages = Vec::new();
+				pool.stacks = Vec::new();
+			}
+		});
+	}
+}
+
+/// Get a buffer holding a copy of `message`, reused from the pool if possible
+pub(crate) fn message(message: &str) -> String {
+	let reused = POOL.try_with(|p| p.borrow_mut().messages.pop()).ok().and_then(|m| m);
+	return match reused {
+		Some(mut buffer) => {
+			buffer.push_str(message);
+			buffer
+		},
+		None => message.to_string()
+	};
+}
+
+/// Get an empty stack trace buffer, reused from the pool if possible
+pub(crate) fn stack() -> Vec<StackEntry> {
+	return POOL.try_with(|p| p.borrow_mut().stacks.pop()).ok().and_then(|s| s).unwrap_or_default();
+}
+
+/// Give buffers back to the pool, if a scope is entered
+pub(crate) fn recycle(mut message: String, mut stack: Vec<StackEntry>) {
+	let _ = POOL.try_with(|p| {
+		if let Ok(mut pool) = p.try_borrow_mut() {
+			if pool.scopes == 0 {
+				return;
+			}
+			if message.capacity() > 0 && pool.messages.len() < MAX_POOLED {
+				message.clear();
+				pool.messages.push(message);
+			}
+			if stack.capacity() > 0 && pool.stacks.len() < MAX_POOLED {
+				stack.clear();
+				pool.stacks.push(stack);
+			}
+		}
+	});
+}
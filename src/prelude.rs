@@ -0,0 +1,11 @@
+//! Re-exports of the traits and types needed by code using the macros of this crate, which
+//! call trait methods on the thrown values
+//! 
+//! ```
+//! #[macro_use] extern crate exceptions;
+//! use exceptions::prelude::*;
+//! # fn fail() -> Result<(), Exception> { throw!("Cannot connect"); }
+//! # fn main() { assert_eq!(fail().unwrap_err().frame_count(), 1); }
+//! ```
+pub use {Throwable, IntoThrowable, TryIntoThrowable, AsAny, HttpStatus, Traced};
+pub use {Exception, StaticException, Throws, StdError, Fallible, WrappedError, StackEntry, StackTrace, DroppedFrames};
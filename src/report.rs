@@ -0,0 +1,100 @@
+//! Reporting of exceptions to pluggable destinations, like error trackers or metrics. Reporters
+//! are registered globally, or for the scope of a thread, so that call sites only need to call
+//! `report!`
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::panic;
+use std::sync::RwLock;
+use {Throwable, Exception, StackEntry, panic_message};
+use sink::{self, Sink};
+
+/// Represent a destination for reported exceptions
+pub trait Reporter: Send + Sync {
+	/// Report `th`
+	fn report(&self, th: &Throwable);
+}
+
+/// Any `Sink` is a `Reporter` writing the traces of reported exceptions
+impl <S: Sink> Reporter for S {
+	fn report(&self, th: &Throwable) {
+		self.write_trace(th);
+	}
+}
+
+static REPORTERS: RwLock<Vec<Box<Reporter>>> = RwLock::new(Vec::new());
+
+thread_local!(static SCOPED: RefCell<Vec<Box<Reporter>>> = const { RefCell::new(Vec::new()) });
+
+/// Register a reporter for all threads
+pub fn add_reporter(reporter: Box<Reporter>) {
+	REPORTERS.write().unwrap_or_else(|e| e.into_inner()).push(reporter);
+}
+
+/// Unregister the reporters added with `add_reporter`, returning them
+pub fn clear_reporters() -> Vec<Box<Reporter>> {
+	let mut reporters = REPORTERS.write().unwrap_or_else(|e| e.into_inner());
+	return reporters.drain(..).collect();
+}
+
+/// Register a reporter for the current thread, until the returned guard is dropped
+pub fn scoped(reporter: Box<Reporter>) -> ScopeGuard {
+	let depth = SCOPED.with(|s| {
+		let mut scoped = s.borrow_mut();
+		scoped.push(reporter);
+		return scoped.len() - 1;
+	});
+	return ScopeGuard{depth: depth, _not_send: PhantomData};
+}
+
+/// Guard of a reporter registered with `scoped`, unregistering it when dropped. Dropping a guard
+/// also unregisters the reporters registered after it
+pub struct ScopeGuard {
+	/// Number of scoped reporters before this one
+	depth: usize,
+	// Scoped reporters belong to a thread
+	_not_send: PhantomData<*const ()>
+}
+
+impl Drop for ScopeGuard {
+	fn drop(&mut self) {
+		let _ = SCOPED.try_with(|s| s.borrow_mut().truncate(self.depth));
+	}
+}
+
+/// Send `th` to the reporters of the current thread, from the most recently registered one,
+/// then to the global ones. If there are none, the trace is written to the sink. Code should
+/// instead call the `report!` macro
+pub fn report(th: &Throwable) {
+	let mut reported = false;
+	SCOPED.with(|s| {
+		// A reporter reporting itself would find the scoped ones borrowed, and skip them
+		if let Ok(scoped) = s.try_borrow() {
+			for reporter in scoped.iter().rev() {
+				reporter.report(th);
+				reported = true;
+			}
+		}
+	});
+	for reporter in REPORTERS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+		reporter.report(th);
+		reported = true;
+	}
+	if !reported {
+		sink::write_trace(th);
+	}
+}
+
+/// Install a panic hook reporting panics as exceptions, having the panic message and a stack
+/// trace entry at the panic location. The previous hook is called afterwards
+pub fn install_panic_hook() {
+	let previous = panic::take_hook();
+	panic::set_hook(Box::new(move |info| {
+		let mut e = Exception::new(format!("Panic: {}", panic_message(info.payload())));
+		if let Some(location) = info.location() {
+			e.push_entry(StackEntry{file: Cow::Owned(location.file().to_string()), line: location.line(), expr: Cow::Borrowed("panic!"), repeat: 1});
+		}
+		report(&e);
+		previous(info);
+	}));
+}
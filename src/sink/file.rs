@@ -0,0 +1,157 @@
+//! `Sink` writing stack traces to a file, with rotation
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use Throwable;
+use config;
+use format::Formatter;
+use super::Sink;
+
+struct Output {
+	file: File,
+	size: u64,
+	opened: Instant
+}
+
+/// `Sink` appending stack traces to a file. The file can be rotated when it becomes too large or
+/// too old: it's then renamed with a `.1` suffix, the previously rotated files being shifted to
+/// `.2`, `.3`, and so on. With the `gzip` feature, rotated files can also be compressed
+pub struct FileSink {
+	path: PathBuf,
+	max_size: Option<u64>,
+	max_age: Option<Duration>,
+	keep: usize,
+	gzip: bool,
+	output: Mutex<Option<Output>>
+}
+
+impl FileSink {
+	/// Create a sink appending to the file at `path`, creating it if needed. By default, the
+	/// file is never rotated
+	pub fn new<P: AsRef<Path>>(path: P) -> io::Result<FileSink> {
+		let path = path.as_ref().to_path_buf();
+		let output = open(&path)?;
+		return Ok(FileSink{path: path, max_size: None, max_age: None, keep: 5, gzip: false, output: Mutex::new(Some(output))});
+	}
+	
+	/// Rotate the file when writing a trace would make it larger than `bytes`
+	pub fn max_size(mut self, bytes: u64) -> FileSink {
+		self.max_size = Some(bytes);
+		return self;
+	}
+	
+	/// Rotate the file when writing a trace once it has been opened for longer than `age`
+	pub fn max_age(mut self, age: Duration) -> FileSink {
+		self.max_age = Some(age);
+		return self;
+	}
+	
+	/// Set the number of rotated files kept, 5 by default. Older ones are deleted
+	pub fn keep(mut self, count: usize) -> FileSink {
+		self.keep = count;
+		return self;
+	}
+	
+	/// Enable or disable the gzip compression of rotated files
+	#[cfg(feature = "gzip")]
+	pub fn gzip(mut self, gzip: bool) -> FileSink {
+		self.gzip = gzip;
+		return self;
+	}
+	
+	/// Rotate the file now
+	pub fn rotate(&self) -> io::Result<()> {
+		let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+		return self.rotate_output(&mut output);
+	}
+	
+	fn rotate_output(&self, output: &mut Option<Output>) -> io::Result<()> {
+		*output = None;
+		let rotated = self.rotate_files();
+		// Reopen the file even if rotating failed, so that a failure doesn't stop the sink
+		*output = Some(open(&self.path)?);
+		return rotated;
+	}
+	
+	fn rotate_files(&self) -> io::Result<()> {
+		// Nothing to rotate if the file is missing, like when it's removed by another process
+		if !self.path.exists() {
+			return Ok(());
+		}
+		if self.keep == 0 {
+			return fs::remove_file(&self.path);
+		}
+		let _ = fs::remove_file(self.rotated_path(self.keep));
+		for i in (1..self.keep).rev() {
+			let from = self.rotated_path(i);
+			if from.exists() {
+				fs::rename(&from, self.rotated_path(i + 1))?;
+			}
+		}
+		let rotated = self.rotated_path(1);
+		if self.gzip {
+			compress(&self.path, &rotated)?;
+			return fs::remove_file(&self.path);
+		}
+		return fs::rename(&self.path, &rotated);
+	}
+	
+	fn rotated_path(&self, index: usize) -> PathBuf {
+		let mut name = self.path.clone().into_os_string();
+		name.push(format!(".{}", index));
+		if self.gzip {
+			name.push(".gz");
+		}
+		return PathBuf::from(name);
+	}
+	
+	fn write(&self, trace: &[u8]) -> io::Result<()> {
+		let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+		let rotate = match *output {
+			Some(ref o) => o.size > 0 && (
+				self.max_size.is_some_and(|max| o.size + trace.len() as u64 > max) ||
+				self.max_age.is_some_and(|max| o.opened.elapsed() >= max)),
+			None => true
+		};
+		let rotated = if rotate { self.rotate_output(&mut output) } else { Ok(()) };
+		if let Some(ref mut o) = *output {
+			o.file.write_all(trace)?;
+			o.size += trace.len() as u64;
+		}
+		return rotated;
+	}
+}
+
+impl Sink for FileSink {
+	/// Write the trace to the file, with the configured format but without colors, even if
+	/// they're forced. Failures are ignored
+	fn write_trace(&self, th: &Throwable) {
+		let _ = self.write(Formatter::from_config(&config::get()).color(false).render(th).as_bytes());
+	}
+}
+
+fn open(path: &Path) -> io::Result<Output> {
+	let file = OpenOptions::new().create(true).append(true).open(path)?;
+	let size = file.metadata()?.len();
+	return Ok(Output{file: file, size: size, opened: Instant::now()});
+}
+
+#[cfg(feature = "gzip")]
+fn compress(from: &Path, to: &Path) -> io::Result<()> {
+	use ::flate2::Compression;
+	use ::flate2::write::GzEncoder;
+	let mut input = File::open(from)?;
+	let mut encoder = GzEncoder::new(File::create(to)?, Compression::default());
+	io::copy(&mut input, &mut encoder)?;
+	encoder.finish()?;
+	return Ok(());
+}
+
+#[cfg(not(feature = "gzip"))]
+fn compress(_: &Path, _: &Path) -> io::Result<()> {
+	return Err(io::Error::other("gzip feature is disabled"));
+}
@@ -0,0 +1,193 @@
+//! `Sink` posting serialized exceptions to an HTTP endpoint, enabled by the `reporter` feature
+use std::sync::Mutex;
+use std::sync::mpsc::{sync_channel, SyncSender, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use ::serde_json::Value;
+use ::ureq;
+use Throwable;
+use json;
+use super::Sink;
+
+enum Message {
+	Trace(Value),
+	Flush(SyncSender<()>)
+}
+
+#[derive(Clone)]
+struct Config {
+	endpoint: String,
+	batch_size: usize,
+	flush_interval: Duration,
+	retries: u32,
+	backoff: Duration,
+	timeout: Duration
+}
+
+struct Worker {
+	sender: SyncSender<Message>,
+	thread: thread::JoinHandle<()>
+}
+
+/// `Sink` sending exceptions serialized by the `json` module to an HTTP endpoint, from a
+/// background thread. Exceptions are queued and posted by batches, as a JSON array. Failed
+/// requests are retried with an exponential backoff, except when the endpoint rejected them
+/// with a 4xx status other than 429. Exceptions are dropped when the queue is full, so that
+/// reporting never blocks the application
+/// 
+/// ```no_run
+/// # use exceptions::sink::{self, HttpReporter};
+/// let reporter = HttpReporter::new("http://reports.local/exceptions").batch_size(50);
+/// sink::set_sink(Box::new(reporter));
+/// ```
+pub struct HttpReporter {
+	config: Config,
+	queue_size: usize,
+	worker: Mutex<Option<Worker>>
+}
+
+impl HttpReporter {
+	/// Create a reporter posting to `endpoint`. The background thread is started when the
+	/// first exception is reported
+	pub fn new(endpoint: &str) -> HttpReporter {
+		return HttpReporter{
+			config: Config{
+				endpoint: endpoint.to_string(),
+				batch_size: 20,
+				flush_interval: Duration::from_secs(5),
+				retries: 3,
+				backoff: Duration::from_millis(500),
+				timeout: Duration::from_secs(10)
+			},
+			queue_size: 1000,
+			worker: Mutex::new(None)
+		};
+	}
+	
+	/// Post at most `size` exceptions per request. Defaults to 20
+	pub fn batch_size(mut self, size: usize) -> HttpReporter {
+		self.config.batch_size = if size == 0 { 1 } else { size };
+		return self;
+	}
+	
+	/// Post the queued exceptions at most `interval` after the first of them was queued, even
+	/// if the batch isn't full. Defaults to 5 seconds
+	pub fn flush_interval(mut self, interval: Duration) -> HttpReporter {
+		self.config.flush_interval = interval;
+		return self;
+	}
+	
+	/// Retry failed requests up to `retries` times, waiting `backoff` before the first retry and
+	/// twice as long before each next one. Defaults to 3 retries and 500 milliseconds
+	pub fn retries(mut self, retries: u32, backoff: Duration) -> HttpReporter {
+		self.config.retries = retries;
+		self.config.backoff = backoff;
+		return self;
+	}
+	
+	/// Set the timeout of each request. Defaults to 10 seconds
+	pub fn timeout(mut self, timeout: Duration) -> HttpReporter {
+		self.config.timeout = timeout;
+		return self;
+	}
+	
+	/// Queue at most `size` exceptions waiting to be posted. Defaults to 1000
+	pub fn queue_size(mut self, size: usize) -> HttpReporter {
+		self.queue_size = size;
+		return self;
+	}
+	
+	/// Post the queued exceptions, and wait until it's done
+	pub fn flush(&self) {
+		let (ack, done) = sync_channel(1);
+		if let Some(ref worker) = *self.worker.lock().unwrap_or_else(|e| e.into_inner()) {
+			if worker.sender.send(Message::Flush(ack)).is_ok() {
+				let _ = done.recv();
+			}
+		}
+	}
+}
+
+impl Sink for HttpReporter {
+	fn write_trace(&self, th: &Throwable) {
+		let mut worker = self.worker.lock().unwrap_or_else(|e| e.into_inner());
+		if worker.is_none() {
+			let (sender, receiver) = sync_channel(self.queue_size);
+			let config = self.config.clone();
+			let thread = match thread::Builder::new().name("exceptions-reporter".to_string()).spawn(move || run(config, receiver)) {
+				Ok(thread) => thread,
+				Err(_) => return
+			};
+			*worker = Some(Worker{sender: sender, thread: thread});
+		}
+		if let Some(ref worker) = *worker {
+			let _ = worker.sender.try_send(Message::Trace(json::to_value(th)));
+		}
+	}
+}
+
+/// Post the queued exceptions, and stop the background thread
+impl Drop for HttpReporter {
+	fn drop(&mut self) {
+		let worker = self.worker.get_mut().unwrap_or_else(|e| e.into_inner()).take();
+		if let Some(worker) = worker {
+			drop(worker.sender);
+			let _ = worker.thread.join();
+		}
+	}
+}
+
+fn run(config: Config, receiver: Receiver<Message>) {
+	let agent = ureq::AgentBuilder::new().timeout(config.timeout).build();
+	let mut batch = Vec::new();
+	let mut started = Instant::now();
+	loop {
+		let message = if batch.is_empty() {
+			receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+		} else {
+			receiver.recv_timeout(config.flush_interval.checked_sub(started.elapsed()).unwrap_or(Duration::from_secs(0)))
+		};
+		match message {
+			Ok(Message::Trace(value)) => {
+				if batch.is_empty() {
+					started = Instant::now();
+				}
+				batch.push(value);
+				if batch.len() < config.batch_size {
+					continue;
+				}
+			},
+			Ok(Message::Flush(ack)) => {
+				post(&agent, &config, &mut batch);
+				let _ = ack.send(());
+				continue;
+			},
+			Err(RecvTimeoutError::Timeout) => {},
+			Err(RecvTimeoutError::Disconnected) => {
+				post(&agent, &config, &mut batch);
+				return;
+			}
+		}
+		post(&agent, &config, &mut batch);
+	}
+}
+
+/// Post and clear `batch`, retrying on failure
+fn post(agent: &ureq::Agent, config: &Config, batch: &mut Vec<Value>) {
+	if batch.is_empty() {
+		return;
+	}
+	let body = Value::Array(batch.split_off(0)).to_string();
+	let mut backoff = config.backoff;
+	for attempt in 0..config.retries + 1 {
+		if attempt > 0 {
+			thread::sleep(backoff);
+			backoff *= 2;
+		}
+		match agent.post(&config.endpoint).set("Content-Type", "application/json").send_string(&body) {
+			Ok(_) => return,
+			Err(ureq::Error::Status(status, _)) if status < 500 && status != 429 => return,
+			Err(_) => {}
+		}
+	}
+}
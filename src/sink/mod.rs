@@ -0,0 +1,170 @@
+//! Destinations where the stack traces of handled exceptions are written
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::mpsc::{sync_channel, SyncSender, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use {Throwable, Exception};
+use grouping::GroupingKey;
+use config;
+
+mod file;
+#[cfg(feature = "reporter")]
+mod http;
+
+pub use self::file::FileSink;
+#[cfg(feature = "reporter")]
+pub use self::http::HttpReporter;
+
+/// Represent a destination for stack traces
+pub trait Sink: Send + Sync {
+	/// Write the stack trace of `th`
+	fn write_trace(&self, th: &Throwable);
+}
+
+/// `Sink` printing stack traces to stderr, like `Throwable::print_stack_trace`. It's the
+/// default sink
+pub struct StderrSink;
+
+impl Sink for StderrSink {
+	fn write_trace(&self, th: &Throwable) {
+		th.print_stack_trace();
+	}
+}
+
+static SINK: RwLock<Option<Box<Sink>>> = RwLock::new(None);
+
+/// Set the sink used by `write_trace`, returning the previous one
+pub fn set_sink(sink: Box<Sink>) -> Option<Box<Sink>> {
+	let mut current = SINK.write().unwrap_or_else(|e| e.into_inner());
+	return current.replace(sink);
+}
+
+/// Write the stack trace of `th` to the sink set with `set_sink`, or to stderr if none was set
+pub fn write_trace(th: &Throwable) {
+	match *SINK.read().unwrap_or_else(|e| e.into_inner()) {
+		Some(ref sink) => sink.write_trace(th),
+		None => StderrSink.write_trace(th)
+	}
+}
+
+struct Suppressed {
+	since: Instant,
+	count: usize,
+	/// Message and kind of the suppressed traces, telling which ones they are in the summary
+	label: String
+}
+
+struct Limiter<S: Sink> {
+	inner: S,
+	window: Duration,
+	seen: Mutex<HashMap<GroupingKey, Suppressed>>
+}
+
+impl <S: Sink> Limiter<S> {
+	/// Report the traces whose window expired at `now`, then forget them. Get how long until the
+	/// next window expires, if any
+	fn expire(&self, now: Instant) -> Option<Duration> {
+		let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+		seen.retain(|_, s| {
+			if now.duration_since(s.since) < self.window {
+				return true;
+			}
+			self.report(s);
+			return false;
+		});
+		return seen.values().map(|s| (s.since + self.window).saturating_duration_since(now)).min();
+	}
+	
+	fn report(&self, s: &Suppressed) {
+		if s.count > 0 {
+			let message = config::get().labels.suppressed.replacen("{}", &s.count.to_string(), 1);
+			self.inner.write_trace(&Exception::new(format!("{}: {}", message, s.label)));
+		}
+	}
+}
+
+struct Timer {
+	/// Dropped to stop the thread
+	stop: SyncSender<()>,
+	thread: thread::JoinHandle<()>
+}
+
+/// `Sink` wrapper writing similar stack traces, having the same `grouping::GroupingKey`, at most
+/// once per time window. The number of traces suppressed during a window is reported with their
+/// message and kind once the window is over, by a background thread started with the first
+/// trace, or when the sink is flushed or dropped
+pub struct RateLimitedSink<S: Sink+'static> {
+	limiter: Arc<Limiter<S>>,
+	timer: Mutex<Option<Timer>>
+}
+
+impl <S: Sink+'static> RateLimitedSink<S> {
+	/// Wrap `inner`, writing similar traces at most once per `window`
+	pub fn new(inner: S, window: Duration) -> RateLimitedSink<S> {
+		let limiter = Limiter{inner: inner, window: window, seen: Mutex::new(HashMap::new())};
+		return RateLimitedSink{limiter: Arc::new(limiter), timer: Mutex::new(None)};
+	}
+	
+	/// Report the number of traces suppressed so far, for all the traces
+	pub fn flush(&self) {
+		let mut seen = self.limiter.seen.lock().unwrap_or_else(|e| e.into_inner());
+		for (_, s) in seen.iter_mut() {
+			self.limiter.report(s);
+			s.count = 0;
+		}
+	}
+	
+	fn start_timer(&self) {
+		let mut timer = self.timer.lock().unwrap_or_else(|e| e.into_inner());
+		if timer.is_some() {
+			return;
+		}
+		let (stop, stopped) = sync_channel(0);
+		let limiter = self.limiter.clone();
+		let run = move || {
+			loop {
+				let wait = limiter.expire(Instant::now()).unwrap_or(limiter.window);
+				if stopped.recv_timeout(wait) != Err(RecvTimeoutError::Timeout) {
+					return;
+				}
+			}
+		};
+		if let Ok(thread) = thread::Builder::new().name("exceptions-rate-limiter".to_string()).spawn(run) {
+			*timer = Some(Timer{stop: stop, thread: thread});
+		}
+	}
+}
+
+impl <S: Sink+'static> Sink for RateLimitedSink<S> {
+	fn write_trace(&self, th: &Throwable) {
+		let now = Instant::now();
+		self.limiter.expire(now);
+		let key = GroupingKey::of(th);
+		{
+			let mut seen = self.limiter.seen.lock().unwrap_or_else(|e| e.into_inner());
+			if let Some(s) = seen.get_mut(&key) {
+				s.count += 1;
+				return;
+			}
+			let label = match th.kind() {
+				"" => th.get_message().to_string(),
+				kind => format!("{} ({})", th.get_message(), kind)
+			};
+			seen.insert(key, Suppressed{since: now, count: 0, label: label});
+		}
+		self.start_timer();
+		self.limiter.inner.write_trace(th);
+	}
+}
+
+impl <S: Sink+'static> Drop for RateLimitedSink<S> {
+	fn drop(&mut self) {
+		let timer = self.timer.get_mut().unwrap_or_else(|e| e.into_inner()).take();
+		if let Some(timer) = timer {
+			drop(timer.stop);
+			let _ = timer.thread.join();
+		}
+		self.flush();
+	}
+}
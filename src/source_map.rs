@@ -0,0 +1,79 @@
+//! Mapping of locations in generated code to the authored source it was generated from, so
+//! that rendered stack traces point at the code to fix. Mappings are consulted when traces
+//! are rendered by `format::Formatter`, the recorded entries are left untouched
+use std::borrow::Cow;
+use std::sync::RwLock;
+use StackEntry;
+
+/// Represent a mapping from locations in generated files to original ones
+pub trait SourceMap: Send + Sync {
+	/// Get the original file and line of `line` in `file`, or `None` if this mapping doesn't
+	/// cover it
+	fn lookup(&self, file: &str, line: u32) -> Option<(String, u32)>;
+}
+
+/// `SourceMap` of a generated file, made of ranges of lines, each one generated from
+/// consecutive lines of an original file
+/// 
+/// ```
+/// # use exceptions::source_map::{self, LineMap};
+/// source_map::add_source_map(Box::new(LineMap::new("src/generated/parser.rs")
+///     .range(1, 120, "grammar/parser.y", 10)));
+/// # assert_eq!(source_map::lookup("src/generated/parser.rs", 3), Some(("grammar/parser.y".to_string(), 12)));
+/// ```
+pub struct LineMap {
+	generated: String,
+	ranges: Vec<(u32, u32, String, u32)>
+}
+
+impl LineMap {
+	/// Create a mapping of the file `generated`, as recorded in stack traces by `file!()`
+	pub fn new(generated: &str) -> LineMap {
+		return LineMap{generated: generated.to_string(), ranges: Vec::new()};
+	}
+	
+	/// Map the lines from `start` to `end` included of the generated file to the lines of
+	/// `original` from `original_start`
+	pub fn range(mut self, start: u32, end: u32, original: &str, original_start: u32) -> LineMap {
+		self.ranges.push((start, end, original.to_string(), original_start));
+		return self;
+	}
+}
+
+impl SourceMap for LineMap {
+	fn lookup(&self, file: &str, line: u32) -> Option<(String, u32)> {
+		if file != self.generated {
+			return None;
+		}
+		return self.ranges.iter().find(|r| r.0 <= line && line <= r.1).map(|r| (r.2.clone(), r.3 + line - r.0));
+	}
+}
+
+static SOURCE_MAPS: RwLock<Vec<Box<SourceMap>>> = RwLock::new(Vec::new());
+
+/// Register a mapping, consulted after the ones registered before it
+pub fn add_source_map(map: Box<SourceMap>) {
+	SOURCE_MAPS.write().unwrap_or_else(|e| e.into_inner()).push(map);
+}
+
+/// Unregister all the mappings, returning them
+pub fn clear_source_maps() -> Vec<Box<SourceMap>> {
+	let mut maps = SOURCE_MAPS.write().unwrap_or_else(|e| e.into_inner());
+	return maps.drain(..).collect();
+}
+
+/// Get the original file and line of `line` in `file`, from the first mapping covering it
+pub fn lookup(file: &str, line: u32) -> Option<(String, u32)> {
+	return SOURCE_MAPS.read().unwrap_or_else(|e| e.into_inner()).iter().filter_map(|m| m.lookup(file, line)).next();
+}
+
+/// Get a copy of `f` pointing at its original location, if a mapping covers it
+pub fn resolve(f: &StackEntry) -> StackEntry {
+	if f.is_boundary() {
+		return f.clone();
+	}
+	return match lookup(&f.file, f.line) {
+		Some((file, line)) => StackEntry{file: Cow::Owned(file), line: line, expr: f.expr.clone(), repeat: f.repeat},
+		None => f.clone()
+	};
+}
@@ -0,0 +1,98 @@
+//! Propagation of exceptions from child processes to their parent, enabled by the `json`
+//! feature. The child writes its exception to stderr as a frame: a line made of `FRAME_PREFIX`
+//! followed by the exception serialized by the `json` module. The parent runs it with `output`,
+//! which finds the frame and attaches the exception as a cause of its own
+//! 
+//! ```no_run
+//! # #[macro_use] extern crate exceptions;
+//! # use std::process::{self, Command};
+//! # use exceptions::prelude::*;
+//! # use exceptions::subprocess;
+//! # fn run() -> Result<(), Exception> { throw!("Cannot start"); }
+//! # fn helper(path: &str) -> Result<(), Exception> {
+//! // In the child
+//! if let Err(e) = run() {
+//!     subprocess::write_to_stderr(&e);
+//!     process::exit(1);
+//! }
+//! // In the parent
+//! let output = try!(subprocess::output(Command::new("helper").arg(path)));
+//! # let _ = output;
+//! # return Ok(());
+//! # }
+//! ```
+use std::io::{stderr, Write};
+use std::panic;
+use std::process::{Command, Output};
+use {Throwable, Exception, thrown, json};
+
+/// Start of the lines holding a serialized exception. It begins with the ASCII record
+/// separator, which isn't expected in regular output
+pub const FRAME_PREFIX: &str = "\u{1e}exception:";
+
+/// Serialize `th` and its causes into a frame, without the line break
+pub fn encode<T: Throwable+?Sized>(th: &T) -> String {
+	return format!("{}{}", FRAME_PREFIX, json::to_json(th));
+}
+
+/// Deserialize the exception of a frame. Return `None` if `line` isn't a frame
+pub fn decode(line: &str) -> Option<Result<Exception, Exception>> {
+	let line = line.trim_end_matches(['\r', '\n']);
+	if !line.starts_with(FRAME_PREFIX) {
+		return None;
+	}
+	return Some(json::from_json(&line[FRAME_PREFIX.len()..]));
+}
+
+/// Write `th` to stderr as a frame, for the parent process to read it
+pub fn write_to_stderr<T: Throwable+?Sized>(th: &T) {
+	let mut err = stderr();
+	let _ = writeln!(err, "{}", encode(th));
+	let _ = err.flush();
+}
+
+/// Run `command` until it exits, collecting its output, like `Command::output`. The frames are
+/// removed from its stderr. If it fails, the returned `Exception` is caused by the exception
+/// of its last frame, if any, behind a boundary naming the process, and records the rest of
+/// its stderr as a context value. The calling location is recorded in the stack trace
+#[track_caller]
+pub fn output(command: &mut Command) -> Result<Output, Exception> {
+	let location = panic::Location::caller();
+	let program = command.get_program().to_string_lossy().into_owned();
+	let mut output = match command.output() {
+		Ok(output) => output,
+		Err(error) => {
+			let mut e = Exception::new_with_cause(format!("Cannot run `{}`", program), Exception::from_error(error));
+			thrown(&mut e);
+			e.push_stack(location.file(), location.line(), "subprocess::output");
+			return Err(e);
+		}
+	};
+	let mut stderr = Vec::new();
+	let mut cause = None;
+	for line in output.stderr.split_inclusive(|b| *b == b'\n') {
+		match decode(&String::from_utf8_lossy(line)) {
+			Some(Ok(e)) => cause = Some(e),
+			Some(Err(_)) | None => stderr.extend_from_slice(line)
+		}
+	}
+	output.stderr = stderr;
+	if output.status.success() {
+		return Ok(output);
+	}
+	let message = format!("`{}` failed with {}", program, output.status);
+	let mut e = match cause {
+		Some(mut cause) => {
+			cause.push_boundary(&format!("process `{}`", program));
+			Exception::new_with_cause(message, cause)
+		},
+		None => Exception::new(message)
+	};
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	if !stderr.trim().is_empty() {
+		e.add_context("stderr", stderr.trim_end().to_string());
+	}
+	thrown(&mut e);
+	e.push_stack(location.file(), location.line(), "subprocess::output");
+	return Err(e);
+}
@@ -0,0 +1,193 @@
+//! Helpers for testing code which throws
+use std::env;
+use std::fs;
+use std::path::Path;
+use Throwable;
+/// Regular expressions usable as `MessagePattern`s, enabled by the `regex` feature
+#[cfg(feature = "regex")]
+pub use regex_lite::Regex;
+
+/// Environment variable which, when set to anything but `0`, makes `compare_baseline` write the
+/// baselines instead of comparing them
+pub const UPDATE_BASELINES: &str = "EXCEPTIONS_UPDATE_BASELINES";
+
+/// Pattern of the messages checked by `Throwable::message_matches`
+pub trait MessagePattern {
+	/// Check if `message` matches the pattern
+	fn matches(&self, message: &str) -> bool;
+}
+
+/// A substring of the message
+impl MessagePattern for str {
+	fn matches(&self, message: &str) -> bool {
+		return message.contains(self);
+	}
+}
+
+/// A substring of the message
+impl MessagePattern for String {
+	fn matches(&self, message: &str) -> bool {
+		return message.contains(self.as_str());
+	}
+}
+
+/// A regular expression found in the message
+#[cfg(feature = "regex")]
+impl MessagePattern for Regex {
+	fn matches(&self, message: &str) -> bool {
+		return self.is_match(message);
+	}
+}
+
+impl <P: MessagePattern+?Sized> MessagePattern for &P {
+	fn matches(&self, message: &str) -> bool {
+		return (**self).matches(message);
+	}
+}
+
+/// Describe the differences between `expected` and `actual`, level by level of their cause
+/// chains: messages, kinds and stack trace entries. The entries are only compared when the
+/// expected `Throwable` has some, so that expectations can be written without them. Return
+/// `None` if there are no differences
+pub fn diff(expected: &Throwable, actual: &Throwable) -> Option<String> {
+	let mut out = String::new();
+	let mut level = 0;
+	let mut pair = (Some(expected), Some(actual));
+	loop {
+		match pair {
+			(Some(expected), Some(actual)) => {
+				diff_level(expected, actual, level, &mut out);
+				pair = (expected.get_cause(), actual.get_cause());
+			},
+			(Some(expected), None) => {
+				out.push_str(&format!("{}: missing, expected \"{}\"\n", level_name(level), expected.get_message()));
+				break;
+			},
+			(None, Some(actual)) => {
+				out.push_str(&format!("{}: unexpected \"{}\"\n", level_name(level), actual.get_message()));
+				break;
+			},
+			(None, None) => break
+		}
+		level += 1;
+	}
+	if out.is_empty() {
+		return None;
+	}
+	return Some(out);
+}
+
+fn level_name(level: usize) -> String {
+	if level == 0 {
+		return "exception".to_string();
+	}
+	return format!("cause #{}", level);
+}
+
+fn kind_name(th: &Throwable) -> &str {
+	if th.kind().is_empty() {
+		return "<none>";
+	}
+	return th.kind();
+}
+
+fn diff_level(expected: &Throwable, actual: &Throwable, level: usize, out: &mut String) {
+	if expected.get_message() != actual.get_message() {
+		out.push_str(&format!("{} message:\n-\t{}\n+\t{}\n", level_name(level), expected.get_message(), actual.get_message()));
+	}
+	if expected.kind() != actual.kind() {
+		out.push_str(&format!("{} kind:\n-\t{}\n+\t{}\n", level_name(level), kind_name(expected), kind_name(actual)));
+	}
+	if expected.frame_count() == 0 {
+		return;
+	}
+	let expected: Vec<String> = expected.frames().map(|f| f.to_string()).collect();
+	let actual: Vec<String> = actual.frames().map(|f| f.to_string()).collect();
+	if expected != actual {
+		out.push_str(&format!("{} stack trace:\n", level_name(level)));
+		for line in diff_lines(&expected, &actual) {
+			out.push_str(&line);
+			out.push('\n');
+		}
+	}
+}
+
+/// Line diff of `expected` and `actual` from their longest common subsequence. Lines are
+/// prefixed by `-` when only expected, `+` when only actual, or a space when common
+fn diff_lines(expected: &[String], actual: &[String]) -> Vec<String> {
+	// lcs[i][j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+	let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+	for i in (0..expected.len()).rev() {
+		for j in (0..actual.len()).rev() {
+			lcs[i][j] = if expected[i] == actual[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+		}
+	}
+	let mut lines = Vec::new();
+	let (mut i, mut j) = (0, 0);
+	while i < expected.len() || j < actual.len() {
+		if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+			lines.push(format!(" \t{}", expected[i]));
+			i += 1;
+			j += 1;
+		} else if j == actual.len() || (i < expected.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+			lines.push(format!("-\t{}", expected[i]));
+			i += 1;
+		} else {
+			lines.push(format!("+\t{}", actual[j]));
+			j += 1;
+		}
+	}
+	return lines;
+}
+
+/// Render `th` and its causes in a form stable across unrelated changes, to be stored as a
+/// baseline: line numbers are left out, and paths use forward slashes
+pub fn normalize(th: &Throwable) -> String {
+	let mut out = String::new();
+	let levels = Some(th).into_iter().chain(th.causes());
+	for (i, level) in levels.enumerate() {
+		if i > 0 {
+			out.push_str("Caused by: ");
+		}
+		out.push_str(level.get_message());
+		out.push('\n');
+		for f in level.frames() {
+			if f.is_boundary() {
+				out.push_str(&format!("\t── {} ──\n", f.expr));
+			} else {
+				out.push_str(&format!("\tat {} [{}]\n", f.expr, f.file.replace('\\', "/")));
+			}
+		}
+	}
+	return out;
+}
+
+/// Compare the normalized trace of `th` to the baseline stored in the file at `path`, returning
+/// the differences as an error. If the `EXCEPTIONS_UPDATE_BASELINES` environment variable is
+/// set, the baseline is written instead, so that the changes can be reviewed in the diff of the
+/// file. The `assert_baseline!` macro panics with the differences
+pub fn compare_baseline<P: AsRef<Path>>(th: &Throwable, path: P) -> Result<(), String> {
+	let path = path.as_ref();
+	let actual = normalize(th);
+	if env::var_os(UPDATE_BASELINES).is_some_and(|v| !v.is_empty() && v != "0") {
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+		}
+		return fs::write(path, actual).map_err(|e| format!("Cannot write {}: {}", path.display(), e));
+	}
+	let expected = match fs::read_to_string(path) {
+		Ok(expected) => expected,
+		Err(e) => return Err(format!("Cannot read the baseline {}: {}. Run with {}=1 to write it", path.display(), e, UPDATE_BASELINES))
+	};
+	if expected.replace("\r\n", "\n") == actual {
+		return Ok(());
+	}
+	let expected: Vec<String> = expected.lines().map(|l| l.to_string()).collect();
+	let actual: Vec<String> = actual.lines().map(|l| l.to_string()).collect();
+	let mut out = format!("Trace differs from the baseline {}, run with {}=1 to update it:\n", path.display(), UPDATE_BASELINES);
+	for line in diff_lines(&expected, &actual) {
+		out.push_str(&line);
+		out.push('\n');
+	}
+	return Err(out);
+}
@@ -0,0 +1,67 @@
+//! Translation of exceptions at API boundaries, for example to turn an internal exception into
+//! a public one while keeping the original as cause
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use {Throwable, IntoThrowable, Exception, thrown};
+
+/// Registry of rules translating errors of one type into `Throwable`s of another type
+pub struct Translator {
+	rules: HashMap<(TypeId, TypeId), Box<Any + Send + Sync>>
+}
+
+/// Same as `Translator::new()`
+impl Default for Translator {
+	fn default() -> Translator {
+		return Translator::new();
+	}
+}
+
+impl Translator {
+	pub fn new() -> Translator {
+		return Translator{rules: HashMap::new()};
+	}
+	
+	/// Register a rule translating errors of type `E` into `T`. It replaces any rule previously
+	/// registered for the same types
+	pub fn register<E, T, F>(&mut self, rule: F) where E: 'static, T: Throwable+'static, F: Fn(E) -> T + Send + Sync + 'static {
+		let rule: Box<Fn(E) -> T + Send + Sync> = Box::new(rule);
+		self.rules.insert((TypeId::of::<E>(), TypeId::of::<T>()), Box::new(rule));
+	}
+	
+	/// Register a rule translating errors of type `E` into an `Exception` with the given message,
+	/// and having the original error as cause
+	pub fn wrap<E: Throwable+'static>(&mut self, message: String) {
+		self.register(move |e: E| Exception::new_with_cause(message.clone(), e));
+	}
+	
+	/// Translate `e` into `T` using the matching registered rule, or `IntoThrowable` if there is none
+	pub fn translate<E, T>(&self, e: E) -> T where E: IntoThrowable<T>+'static, T: Throwable+'static {
+		let rule = self.rules.get(&(TypeId::of::<E>(), TypeId::of::<T>()))
+			.and_then(|r| r.downcast_ref::<Box<Fn(E) -> T + Send + Sync>>());
+		return match rule {
+			Some(rule) => rule(e),
+			None => e.into_throwable()
+		};
+	}
+}
+
+static ACTIVE: RwLock<Option<Translator>> = RwLock::new(None);
+
+/// Set the translator used by the `translate!` macro, returning the previous one
+pub fn set_translator(translator: Translator) -> Option<Translator> {
+	let mut active = ACTIVE.write().unwrap_or_else(|e| e.into_inner());
+	return active.replace(translator);
+}
+
+/// Translate `e` with the translator set with `set_translator`, then push the given
+/// stack trace information on the result. Code should instead call the `translate!` macro
+pub fn translate<E, T>(e: E, file: &'static str, line: u32, expr: &'static str) -> T where E: IntoThrowable<T>+'static, T: Throwable+'static {
+	let mut th = match *ACTIVE.read().unwrap_or_else(|e| e.into_inner()) {
+		Some(ref translator) => translator.translate(e),
+		None => e.into_throwable()
+	};
+	thrown(&mut th);
+	th.push_stack(file, line, expr);
+	return th;
+}
@@ -0,0 +1,33 @@
+//! Check the `actix-web` responses of exceptions
+#![cfg(feature = "actix")]
+extern crate exceptions;
+extern crate actix_web;
+
+use actix_web::{HttpResponse, ResponseError};
+use actix_web::http::StatusCode;
+use actix_web::http::header;
+use exceptions::prelude::*;
+
+fn teapot(_e: &Exception) -> StatusCode {
+	return StatusCode::IM_A_TEAPOT;
+}
+
+fn empty(_e: &Exception, status: StatusCode) -> HttpResponse {
+	return HttpResponse::build(status).finish();
+}
+
+// The mapper and the renderer are global, so they're only set once the default response is
+// checked
+#[test]
+fn status_mapper_and_body_renderer_replace_the_defaults() {
+	let mut e = Exception::new("No such user".to_string());
+	e.set_http_status(404);
+	let response = e.error_response();
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	assert_eq!(response.headers().get(header::CONTENT_TYPE).unwrap(), "application/problem+json");
+	exceptions::set_actix_status_mapper(teapot);
+	exceptions::set_actix_body_renderer(empty);
+	let response = Exception::new("No such user".to_string()).error_response();
+	assert_eq!(response.status(), StatusCode::IM_A_TEAPOT);
+	assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+}
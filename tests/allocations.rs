@@ -0,0 +1,61 @@
+//! Check the number of heap allocations made when throwing, with a counting global allocator
+#[macro_use]
+extern crate exceptions;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use exceptions::{Throwable, Exception};
+use exceptions::pool::ThrowScope;
+
+struct CountingAllocator;
+
+thread_local!(static ALLOCATIONS: Cell<usize> = const { Cell::new(0) });
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let _ = ALLOCATIONS.try_with(|a| a.set(a.get() + 1));
+		return System.alloc(layout);
+	}
+	
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+	}
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Count the allocations made by `f` on the current thread
+fn allocations<F: FnOnce()>(f: F) -> usize {
+	let before = ALLOCATIONS.with(|a| a.get());
+	f();
+	return ALLOCATIONS.with(|a| a.get()) - before;
+}
+
+fn throw_literal() -> Result<(), Exception> {
+	throw!("static message");
+}
+
+#[test]
+fn static_message_does_not_allocate() {
+	assert_eq!(allocations(|| drop(Exception::from_static("static message"))), 0);
+}
+
+#[test]
+fn throwing_a_literal_allocates_only_the_stack_trace() {
+	assert_eq!(allocations(|| drop(throw_literal())), 1);
+}
+
+#[test]
+fn throwing_a_literal_in_a_scope_reuses_the_stack_trace() {
+	let _scope = ThrowScope::enter();
+	drop(throw_literal());
+	assert_eq!(allocations(|| drop(throw_literal())), 0);
+}
+
+#[test]
+fn thrown_literal_keeps_its_message() {
+	let e = throw_literal().unwrap_err();
+	assert_eq!(e.get_message(), "static message");
+	assert_eq!(e.frame_count(), 1);
+}
@@ -0,0 +1,29 @@
+//! Check the conversions between `Exception` and `anyhow::Error`
+#![cfg(feature = "anyhow")]
+#[macro_use]
+extern crate exceptions;
+extern crate anyhow;
+
+use exceptions::prelude::*;
+
+fn fail() -> Result<(), Exception> {
+	let cause = Exception::new("Connection reset".to_string());
+	throw!(Exception::new_with_cause("Cannot fetch".to_string(), cause));
+}
+
+#[test]
+fn causes_become_contexts() {
+	let err = anyhow::Error::from(fail().unwrap_err());
+	let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+	assert_eq!(chain.len(), 2);
+	assert!(chain[0].starts_with("Cannot fetch\n\tat"));
+	assert_eq!(chain[1], "Connection reset");
+}
+
+#[test]
+fn contexts_become_causes() {
+	let err = anyhow::Error::msg("Connection reset").context("Cannot fetch");
+	let e: Exception = err.into_throwable();
+	assert_eq!(e.get_message(), "Cannot fetch");
+	assert_eq!(e.get_cause().unwrap().get_message(), "Connection reset");
+}
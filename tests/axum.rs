@@ -0,0 +1,24 @@
+//! Check the `axum` responses of exceptions
+#![cfg(feature = "axum")]
+extern crate exceptions;
+extern crate axum;
+
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use exceptions::prelude::*;
+
+#[test]
+fn response_has_the_status_of_the_exception() {
+	let mut e = Exception::new("No such user".to_string());
+	e.set_http_status(404);
+	let response = e.into_response();
+	assert_eq!(response.status(), StatusCode::NOT_FOUND);
+	assert_eq!(response.headers()[header::CONTENT_TYPE], "application/problem+json");
+}
+
+#[test]
+fn invalid_statuses_are_internal_errors() {
+	let mut e = Exception::new("No such user".to_string());
+	e.set_http_status(1000);
+	assert_eq!(e.into_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
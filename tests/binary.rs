@@ -0,0 +1,45 @@
+//! Check the binary encoding of exceptions
+#[macro_use]
+extern crate exceptions;
+
+use exceptions::prelude::*;
+use exceptions::binary;
+
+fn fail() -> Result<(), Exception> {
+	let cause = Exception::new("Connection reset".to_string());
+	throw!(Exception::new_with_cause("Cannot fetch".to_string(), cause); url = "/users");
+}
+
+#[test]
+fn decoded_exception_matches_the_encoded_one() {
+	let e = fail().unwrap_err();
+	let decoded = binary::decode(&binary::encode(&e)).unwrap();
+	assert_eq!(decoded.get_message(), "Cannot fetch");
+	assert_eq!(decoded.get_context(), e.get_context());
+	assert_eq!(decoded.frame_count(), 1);
+	assert!(decoded.top_frame().unwrap().same_location(e.top_frame().unwrap()));
+	assert_eq!(decoded.get_cause().unwrap().get_message(), "Connection reset");
+}
+
+#[test]
+fn truncated_input_is_rejected() {
+	let bytes = binary::encode(&fail().unwrap_err());
+	assert!(binary::decode(&bytes[..bytes.len() - 1]).is_err());
+}
+
+/// Encode a chain of `levels` levels having an empty message and kind, and no entries nor
+/// context values
+fn chain(levels: usize) -> Vec<u8> {
+	let mut bytes = binary::MAGIC.to_vec();
+	for _ in 1..levels {
+		bytes.extend_from_slice(&[0, 0, 0, 0, 1]);
+	}
+	bytes.extend_from_slice(&[0, 0, 0, 0, 0]);
+	return bytes;
+}
+
+#[test]
+fn too_deep_chains_are_rejected() {
+	assert!(binary::decode(&chain(binary::MAX_DEPTH)).is_ok());
+	assert!(binary::decode(&chain(binary::MAX_DEPTH + 1)).is_err());
+}
@@ -0,0 +1,67 @@
+//! Check the arms of `catch!`
+#[macro_use]
+extern crate exceptions;
+
+use std::fmt;
+use exceptions::prelude::*;
+
+throwable_error! {
+	#[derive(Debug)]
+	pub struct FormatException(fmt::Error);
+}
+
+fn fail(message: &'static str) -> Result<u32, Exception> {
+	throw!(message);
+}
+
+fn fail_with_cause() -> Result<u32, Exception> {
+	let cause: FormatException = fmt::Error.into_throwable();
+	return Err(Exception::new_with_cause("Cannot render".to_string(), cause));
+}
+
+fn guard_then_catch_all(message: &'static str) -> Result<u32, Exception> {
+	let value = catch! { fail(message);
+		e if e.get_message() == "retry" => 1,
+		_ e => 2
+	};
+	return Ok(value);
+}
+
+fn guard_then_typed(r: Result<u32, Exception>) -> Result<u32, Exception> {
+	let value = catch! { r;
+		e if e.get_message() == "retry" => 1,
+		as FormatException f => f.error().to_string().len() as u32
+	};
+	return Ok(value);
+}
+
+#[test]
+fn guard_arm_catches_when_it_holds() {
+	assert_eq!(guard_then_catch_all("retry").ok(), Some(1));
+}
+
+#[test]
+fn catch_all_arm_after_guard_catches_the_rest() {
+	assert_eq!(guard_then_catch_all("other").ok(), Some(2));
+}
+
+#[test]
+fn typed_arm_after_guard_catches_a_cause() {
+	assert_eq!(guard_then_typed(fail_with_cause()).ok(), Some(fmt::Error.to_string().len() as u32));
+}
+
+#[test]
+fn uncaught_error_is_returned() {
+	let e = guard_then_typed(fail("other")).unwrap_err();
+	assert_eq!(e.get_message(), "other");
+	assert!(e.handled_at().is_none());
+}
+
+#[test]
+fn catching_records_where_the_error_was_handled() {
+	let mut handled = None;
+	let _ = (|| -> Result<u32, Exception> {
+		return Ok(catch! { fail("x"); _ e => { handled = e.handled_at().map(|f| f.expr.to_string()); 0 } });
+	})();
+	assert_eq!(handled.as_deref(), Some("catch!"));
+}
@@ -0,0 +1,24 @@
+//! Check the walks along cause chains
+extern crate exceptions;
+
+use exceptions::prelude::*;
+
+fn chain() -> Exception {
+	let root = Exception::new("Connection reset".to_string());
+	let cause = Exception::new_with_cause("Cannot fetch".to_string(), root);
+	return Exception::new_with_cause("Cannot load profile".to_string(), cause);
+}
+
+#[test]
+fn root_cause_is_the_innermost_cause() {
+	assert_eq!(chain().root_cause().get_message(), "Connection reset");
+}
+
+#[test]
+fn root_cause_of_a_trait_object() {
+	let e = chain();
+	let th: &Throwable = &e;
+	assert_eq!(th.root_cause().get_message(), "Connection reset");
+	let boxed: Box<Throwable> = Box::new(Exception::new("Cannot fetch".to_string()));
+	assert_eq!(boxed.root_cause().get_message(), "Cannot fetch");
+}
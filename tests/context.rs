@@ -0,0 +1,46 @@
+//! Check the context labels attached to exceptions
+#[macro_use]
+extern crate exceptions;
+
+use exceptions::prelude::*;
+use exceptions::context::{self, ContextGuard, CONTEXT_KEY};
+
+fn read() -> Result<(), Exception> {
+	throw!(Exception::new_with_cause("Cannot read".to_string(), Exception::new("Disk error".to_string())));
+}
+
+fn handle() -> Result<(), Exception> {
+	try!(read());
+	return Ok(());
+}
+
+#[test]
+fn dropping_a_guard_removes_its_own_label() {
+	let request = ContextGuard::enter("request 7");
+	let user = ContextGuard::enter("user 42");
+	drop(request);
+	assert!(context::labels().is_empty());
+	drop(user);
+	assert!(context::labels().is_empty());
+}
+
+#[test]
+fn guards_dropped_in_order_restore_the_outer_labels() {
+	let _request = ContextGuard::enter("request 7");
+	{
+		let _user = ContextGuard::enter("user 42");
+		assert_eq!(context::labels(), vec!["request 7", "user 42"]);
+	}
+	assert_eq!(context::labels(), vec!["request 7"]);
+	let _step = ContextGuard::enter("step 1");
+	assert_eq!(context::labels(), vec!["request 7", "step 1"]);
+}
+
+#[test]
+fn labels_are_recorded_once_on_the_thrown_exception() {
+	let _request = ContextGuard::enter("request 7");
+	assert!(Exception::new("Not thrown".to_string()).get_context().is_empty());
+	let e = handle().unwrap_err();
+	assert_eq!(e.get_context(), &[(CONTEXT_KEY.to_string(), "request 7".to_string())]);
+	assert!(e.get_cause().unwrap().get_context().is_empty());
+}
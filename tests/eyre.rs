@@ -0,0 +1,37 @@
+//! Check the conversions between `Exception` and `eyre::Report`
+#![cfg(feature = "eyre")]
+#[macro_use]
+extern crate exceptions;
+extern crate eyre;
+
+use exceptions::prelude::*;
+
+fn fail() -> Result<(), Exception> {
+	let cause = Exception::new("Connection reset".to_string());
+	throw!(Exception::new_with_cause("Cannot fetch".to_string(), cause));
+}
+
+/// Install the handler before any report is created, since `eyre` otherwise installs its
+/// default one
+fn install_handler() {
+	let _ = exceptions::install_eyre_handler();
+}
+
+#[test]
+fn entries_are_kept_through_a_report() {
+	install_handler();
+	let report = eyre::Report::from(fail().unwrap_err());
+	assert_eq!(report.chain().count(), 2);
+	let e: Exception = report.into_throwable();
+	assert_eq!(e.get_message(), "Cannot fetch");
+	assert_eq!(e.frame_count(), 1);
+	assert_eq!(e.get_cause().unwrap().get_message(), "Connection reset");
+}
+
+#[test]
+fn handler_renders_the_entries() {
+	install_handler();
+	let rendered = format!("{:?}", eyre::Report::from(fail().unwrap_err()));
+	assert!(rendered.starts_with("Cannot fetch\n\tat "));
+	assert!(rendered.ends_with("\nCaused by: Connection reset"));
+}
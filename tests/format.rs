@@ -0,0 +1,79 @@
+//! Check the rendering of stack traces
+extern crate exceptions;
+
+use std::env;
+use exceptions::prelude::*;
+use exceptions::format::{Formatter, Style};
+
+#[test]
+fn clicolor_force_enables_colors() {
+	env::set_var("CLICOLOR_FORCE", "1");
+	let trace = Formatter::new().color(false).detect_color().render(&Exception::new("Cannot connect".to_string()));
+	env::remove_var("CLICOLOR_FORCE");
+	assert!(trace.contains('\x1b'));
+}
+
+/// Create an `Exception` having a boundary entry for each of the `labels`, from the outermost one
+fn with_entries(message: &str, labels: &[&str]) -> Exception {
+	let mut e = Exception::new(message.to_string());
+	for label in labels.iter().rev() {
+		e.push_boundary(label);
+	}
+	return e;
+}
+
+#[test]
+fn flat_traces_show_the_dropped_entries_of_each_level() {
+	let mut cause = with_entries("Connection reset", &["main", "fetch", "read", "recv"]);
+	cause.set_max_frames(2);
+	let mut e = Exception::new_with_cause("Cannot fetch".to_string(), cause);
+	e.push_boundary("handle");
+	e.push_boundary("serve");
+	e.push_boundary("main");
+	e.truncate_frames(1);
+	let trace = Formatter::new().style(Style::Flat).render(&e);
+	let lines: Vec<&str> = trace.lines().map(|l| l.trim()).collect();
+	assert_eq!(lines.iter().filter(|l| **l == "... 2 frames omitted ...").count(), 2);
+}
+
+#[test]
+fn retained_entries_keep_the_position_of_the_dropped_ones() {
+	let mut e = with_entries("Connection reset", &["main", "serve", "handle", "fetch", "read", "recv"]);
+	e.set_max_frames(4);
+	assert_eq!(e.dropped_frames(), DroppedFrames{outermost: 0, middle: 2, middle_at: 2});
+	e.retain_frames(|f| f.expr != "main");
+	assert_eq!(e.dropped_frames(), DroppedFrames{outermost: 0, middle: 2, middle_at: 1});
+	let trace = Formatter::new().render(&e);
+	let lines: Vec<&str> = trace.lines().map(|l| l.trim()).collect();
+	let omitted = lines.iter().position(|l| *l == "... 2 frames omitted ...").unwrap();
+	assert!(lines[omitted - 1].contains("serve"));
+	assert!(lines[omitted + 1].contains("read"));
+	e.retain_frames(|f| f.expr != "serve");
+	assert_eq!(e.dropped_frames(), DroppedFrames{outermost: 2, middle: 0, middle_at: 0});
+}
+
+#[test]
+fn verbose_paths_are_resolved_before_stripping_prefixes() {
+	let mut e = Exception::new("Connection reset".to_string());
+	e.push_stack("/build/app/src/net.rs", 12, "stream.read(&mut buffer)");
+	let trace = Formatter::new().style(Style::Verbose).strip_path_prefix("/build/app/").render(&e);
+	assert!(trace.contains("[/build/app/src/net.rs:12] in crate::net"));
+}
+
+#[test]
+fn flat_entries_are_prefixed_with_their_level() {
+	let mut cause = Exception::new("Connection reset".to_string());
+	cause.push_stack("src/net.rs", 12, "stream.read(&mut buffer)");
+	cause.push_stack("src/client.rs", 30, "fetch(url)");
+	let mut e = Exception::new_with_cause("Cannot fetch".to_string(), cause);
+	e.push_stack("src/client.rs", 30, "fetch(url)");
+	e.push_stack("src/main.rs", 5, "run()");
+	let trace = Formatter::new().style(Style::Flat).render(&e);
+	let frames: Vec<&str> = trace.lines().map(|l| l.trim()).filter(|l| l.starts_with('[') && l.contains(" at ")).collect();
+	assert_eq!(frames, vec![
+		"[1] at stream.read(&mut buffer) [src/net.rs:12]",
+		"[1] at fetch(url) [src/client.rs:30]",
+		"[0] at fetch(url) [src/client.rs:30]",
+		"[0] at run() [src/main.rs:5]"
+	]);
+}
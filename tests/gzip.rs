@@ -0,0 +1,26 @@
+//! Check the compression of the files rotated by `FileSink`
+#![cfg(feature = "gzip")]
+extern crate exceptions;
+extern crate flate2;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::process;
+use flate2::read::GzDecoder;
+use exceptions::prelude::*;
+use exceptions::sink::{FileSink, Sink};
+
+#[test]
+fn rotated_files_are_compressed() {
+	let path = env::temp_dir().join(format!("exceptions-gzip-{}.log", process::id()));
+	let sink = FileSink::new(&path).unwrap().gzip(true);
+	sink.write_trace(&Exception::new("Cannot connect".to_string()));
+	sink.rotate().unwrap();
+	let rotated = env::temp_dir().join(format!("exceptions-gzip-{}.log.1.gz", process::id()));
+	let mut written = String::new();
+	GzDecoder::new(File::open(&rotated).unwrap()).read_to_string(&mut written).unwrap();
+	let _ = fs::remove_file(&path);
+	let _ = fs::remove_file(&rotated);
+	assert!(written.contains("Cannot connect"));
+}
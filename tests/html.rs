@@ -0,0 +1,56 @@
+//! Check the HTML rendering of stack traces
+extern crate exceptions;
+
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use exceptions::prelude::*;
+use exceptions::StackEntry;
+use exceptions::html::HtmlReport;
+
+/// Create a directory containing a `root` directory with a `main.rs` file, and a `secret.rs`
+/// file outside of it
+fn sources(name: &str) -> PathBuf {
+	let dir = env::temp_dir().join(format!("exceptions-html-{}-{}", name, process::id()));
+	fs::create_dir_all(dir.join("root")).unwrap();
+	fs::write(dir.join("root").join("main.rs"), "fn main() {\n\tlet answer = 42;\n}\n").unwrap();
+	fs::write(dir.join("secret.rs"), "let password = \"hunter2\";\n").unwrap();
+	return dir;
+}
+
+fn exception_at(files: &[String]) -> Exception {
+	let mut e = Exception::new("Cannot start".to_string());
+	for file in files {
+		e.push_entry(StackEntry{file: Cow::Owned(file.clone()), line: 1, expr: Cow::Borrowed("start()"), repeat: 1});
+	}
+	return e;
+}
+
+#[test]
+fn snippets_are_disabled_by_default() {
+	let dir = sources("default");
+	let e = exception_at(&[dir.join("root").join("main.rs").to_string_lossy().into_owned()]);
+	let page = HtmlReport::new().render(&e);
+	let _ = fs::remove_dir_all(&dir);
+	assert!(!page.contains("answer"));
+}
+
+#[test]
+fn snippets_are_read_from_the_source_roots() {
+	let dir = sources("roots");
+	let e = exception_at(&["main.rs".to_string()]);
+	let page = HtmlReport::new().source_root(dir.join("root")).render(&e);
+	let _ = fs::remove_dir_all(&dir);
+	assert!(page.contains("answer"));
+}
+
+#[test]
+fn files_outside_of_the_source_roots_are_not_read() {
+	let dir = sources("outside");
+	let e = exception_at(&["../secret.rs".to_string(), dir.join("secret.rs").to_string_lossy().into_owned()]);
+	let page = HtmlReport::new().source_root(dir.join("root")).render(&e);
+	let _ = fs::remove_dir_all(&dir);
+	assert!(!page.contains("hunter2"));
+}
@@ -0,0 +1,45 @@
+//! Check the JSON serialization of exceptions
+#![cfg(feature = "json")]
+#[macro_use]
+extern crate exceptions;
+
+use std::borrow::Cow;
+use exceptions::prelude::*;
+use exceptions::json;
+use exceptions::context::ContextGuard;
+
+fn fail() -> Result<(), Exception> {
+	let cause = Exception::new("Connection reset".to_string());
+	throw!(Exception::new_with_cause("Cannot fetch".to_string(), cause); url = "/users");
+}
+
+#[test]
+fn deserialized_exception_matches_the_serialized_one() {
+	let e = fail().unwrap_err();
+	let parsed = json::from_json(&json::to_json(&e)).unwrap();
+	assert_eq!(parsed.get_message(), "Cannot fetch");
+	assert_eq!(parsed.get_context(), e.get_context());
+	assert!(parsed.top_frame().unwrap().same_location(e.top_frame().unwrap()));
+	assert_eq!(parsed.get_cause().unwrap().get_message(), "Connection reset");
+}
+
+#[test]
+fn deserialized_entries_are_owned() {
+	let parsed = json::from_json(r#"{"message": "Cannot fetch", "frames": [{"file": "src/client.rs", "line": 12, "expr": "get(url)"}]}"#).unwrap();
+	let top = parsed.top_frame().unwrap();
+	assert_eq!(top.file, "src/client.rs");
+	assert!(match top.file { Cow::Owned(_) => true, Cow::Borrowed(_) => false });
+}
+
+#[test]
+fn invalid_entries_are_rejected() {
+	assert!(json::from_json(r#"{"message": "Cannot fetch", "frames": [{"file": "src/client.rs"}]}"#).is_err());
+}
+
+#[test]
+fn deserialized_exception_keeps_the_serialized_context() {
+	let e = fail().unwrap_err();
+	let _request = ContextGuard::enter("request 7");
+	let parsed = json::from_json(&json::to_json(&e)).unwrap();
+	assert_eq!(parsed.get_context(), e.get_context());
+}
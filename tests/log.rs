@@ -0,0 +1,38 @@
+//! Check the logging of stack traces through the `log` crate
+#![cfg(feature = "log")]
+extern crate exceptions;
+extern crate log;
+
+use std::sync::Mutex;
+use exceptions::prelude::*;
+use exceptions::LogSink;
+use exceptions::sink::Sink;
+use log::{Level, Log, Metadata, Record};
+
+/// Logger keeping the level, target and message of the records
+struct Records(Mutex<Vec<(Level, String, String)>>);
+
+impl Log for Records {
+	fn enabled(&self, _metadata: &Metadata) -> bool {
+		return true;
+	}
+	
+	fn log(&self, record: &Record) {
+		self.0.lock().unwrap().push((record.level(), record.target().to_string(), record.args().to_string()));
+	}
+	
+	fn flush(&self) {}
+}
+
+static RECORDS: Records = Records(Mutex::new(Vec::new()));
+
+#[test]
+fn sink_logs_traces_at_its_level() {
+	let _ = log::set_logger(&RECORDS);
+	log::set_max_level(log::LevelFilter::Trace);
+	LogSink::new().level(Level::Warn).write_trace(&Exception::new("Cannot connect".to_string()));
+	let records = RECORDS.0.lock().unwrap();
+	let record = records.iter().find(|r| r.2.contains("Cannot connect")).unwrap();
+	assert_eq!(record.0, Level::Warn);
+	assert_eq!(record.1, "exceptions");
+}
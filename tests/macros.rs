@@ -0,0 +1,71 @@
+//! Check the procedural macros of the `macros` feature
+#![cfg(feature = "macros")]
+#[macro_use]
+extern crate exceptions;
+
+use std::num::ParseIntError;
+use exceptions::prelude::*;
+use exceptions::{throws, try_spanned, IntoThrowable};
+
+#[derive(IntoThrowable)]
+#[kind("config")]
+enum ConfigError {
+	#[message("Cannot read {path}")]
+	#[kind("config.io")]
+	Read { path: String },
+	#[message("Invalid value at line {0}")]
+	Invalid(u32),
+	#[message("Cannot parse: {0}")]
+	#[from]
+	Parse(ParseIntError)
+}
+
+#[throws]
+fn port(text: &str) -> u16 {
+	if text.is_empty() {
+		throw!("No port set");
+	}
+	text.len() as u16
+}
+
+#[test]
+fn throws_wraps_the_value_of_the_body() {
+	assert_eq!(port("8080").ok(), Some(4));
+	assert_eq!(port("").unwrap_err().get_message(), "No port set");
+}
+
+#[test]
+fn derived_exceptions_have_the_message_and_kind_of_the_variant() {
+	let e: Exception = ConfigError::Read{path: "config.toml".to_string()}.into_throwable();
+	assert_eq!(e.get_message(), "Cannot read config.toml");
+	assert!(e.is_instance_of("config.io"));
+	assert_eq!(e.variant_of::<ConfigError>(), Some("Read"));
+	let e: Exception = ConfigError::Invalid(3).into_throwable();
+	assert_eq!(e.get_message(), "Invalid value at line 3");
+	assert!(e.is_instance_of("config"));
+}
+
+fn parse(text: &str) -> Result<u16, ConfigError> {
+	return Ok(text.parse::<u16>()?);
+}
+
+#[test]
+fn from_variants_are_converted_by_the_question_mark() {
+	let e: Exception = parse("x").err().unwrap().into_throwable();
+	assert_eq!(e.variant_of::<ConfigError>(), Some("Parse"));
+}
+
+fn fail() -> Result<u16, Exception> {
+	throw!("Cannot connect");
+}
+
+fn connect() -> Result<u16, Exception> {
+	let port = try_spanned!(fail());
+	return Ok(port);
+}
+
+#[test]
+fn try_spanned_records_the_source_text() {
+	let e = connect().unwrap_err();
+	assert!(e.frames().next().unwrap().expr.starts_with("fail()"));
+}
@@ -0,0 +1,54 @@
+//! Check the calls of the `metrics` hook
+#[macro_use]
+extern crate exceptions;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use exceptions::prelude::*;
+use exceptions::{StackEntry, filter, metrics};
+
+static THROWN: AtomicUsize = AtomicUsize::new(0);
+
+// The hook is global, so the tests setting it run one at a time
+static HOOK: Mutex<()> = Mutex::new(());
+
+fn count(_th: &Throwable) {
+	THROWN.fetch_add(1, Ordering::SeqCst);
+}
+
+fn fail() -> Result<(), Exception> {
+	throw!("Cannot connect");
+}
+
+fn retry() -> Result<(), Exception> {
+	try!(fail());
+	return Ok(());
+}
+
+#[test]
+fn hook_is_called_once_even_without_recorded_entries() {
+	let _hook = HOOK.lock().unwrap_or_else(|e| e.into_inner());
+	let before = THROWN.load(Ordering::SeqCst);
+	metrics::set_throw_hook(count);
+	filter::add_capture_filter(Box::new(|_: &StackEntry| false));
+	let e = retry().unwrap_err();
+	metrics::remove_throw_hook();
+	filter::clear_filters();
+	assert_eq!(e.frame_count(), 0);
+	assert_eq!(THROWN.load(Ordering::SeqCst), before + 1);
+}
+
+#[test]
+fn hook_is_not_called_when_creating_exceptions() {
+	let _hook = HOOK.lock().unwrap_or_else(|e| e.into_inner());
+	metrics::set_throw_hook(count);
+	let before = THROWN.load(Ordering::SeqCst);
+	let e = exception!("Cannot connect to {}", "db");
+	let _ = Exception::format(format_args!("Cannot connect to {}", "db"));
+	let after = THROWN.load(Ordering::SeqCst);
+	let thrown = (|| -> Result<(), Exception> { throw!(e); })();
+	metrics::remove_throw_hook();
+	assert_eq!(after, before);
+	assert!(thrown.is_err());
+	assert_eq!(THROWN.load(Ordering::SeqCst), after + 1);
+}
@@ -0,0 +1,27 @@
+//! Check the `?` operator on `nightly::Throws`
+#![cfg(feature = "nightly")]
+extern crate exceptions;
+
+use exceptions::prelude::*;
+use exceptions::nightly::Throws;
+
+fn parse(text: &str) -> Throws<u32> {
+	return Throws::from(text.parse::<u32>().map_err(|e| e.into_throwable()));
+}
+
+fn load(text: &str) -> Throws<u32> {
+	let value = parse(text)?;
+	return Throws::ok(value + 1);
+}
+
+#[test]
+fn question_mark_records_an_entry() {
+	let e = load("x").into_result().unwrap_err();
+	assert_eq!(e.frame_count(), 1);
+	assert_eq!(e.top_frame().unwrap().expr, "?");
+}
+
+#[test]
+fn question_mark_passes_values() {
+	assert_eq!(load("41").into_result().unwrap(), 42);
+}
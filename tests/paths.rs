@@ -0,0 +1,29 @@
+//! Check the macros invoked by path, without `#[macro_use]`, which requires them to refer to
+//! each other through `$crate`
+extern crate exceptions;
+
+use exceptions::prelude::*;
+
+fn fail(port: u16) -> Result<(), Exception> {
+	exceptions::throw!(Exception::new("Cannot connect".to_string()); port = port, host = "localhost");
+}
+
+fn retry() -> Result<u32, Exception> {
+	let value = exceptions::catch! { fail(8080).map(|_| 0);
+		e if e.get_message() == "Timeout" => 1,
+		_ e => 2
+	};
+	return Ok(value);
+}
+
+#[test]
+fn throw_with_context() {
+	let e = fail(8080).unwrap_err();
+	assert_eq!(e.get_context().len(), 2);
+	assert!(exceptions::matches_exception!(e, Exception { .. }));
+}
+
+#[test]
+fn catch_with_arms() {
+	assert_eq!(retry().unwrap(), 2);
+}
@@ -0,0 +1,23 @@
+//! Check the regular expressions usable as message patterns
+#![cfg(feature = "regex")]
+#[macro_use]
+extern crate exceptions;
+
+use exceptions::prelude::*;
+use exceptions::testing::Regex;
+
+fn parse(text: &str) -> Result<u32, Exception> {
+	throw!(format!("Invalid number {:?} at column {}", text, 3));
+}
+
+#[test]
+fn messages_are_matched_by_regular_expressions() {
+	let e = parse("x").unwrap_err();
+	assert!(e.message_matches(Regex::new(r"column \d+$").unwrap()));
+	assert!(!e.message_matches(Regex::new(r"^column").unwrap()));
+}
+
+#[test]
+fn assert_throws_accepts_regular_expressions() {
+	assert_throws!(parse("x"), message: Regex::new(r#"Invalid number "x""#).unwrap());
+}
@@ -0,0 +1,28 @@
+//! Check the registration of reporters
+extern crate exceptions;
+
+use std::sync::{Arc, Mutex};
+use exceptions::prelude::*;
+use exceptions::report::{self, Reporter};
+
+struct Recorder {
+	name: &'static str,
+	seen: Arc<Mutex<Vec<&'static str>>>
+}
+
+impl Reporter for Recorder {
+	fn report(&self, _th: &Throwable) {
+		self.seen.lock().unwrap().push(self.name);
+	}
+}
+
+#[test]
+fn dropping_a_scope_guard_unregisters_the_reporters_after_it() {
+	let seen = Arc::new(Mutex::new(Vec::new()));
+	let outer = report::scoped(Box::new(Recorder{name: "outer", seen: seen.clone()}));
+	let _inner = report::scoped(Box::new(Recorder{name: "inner", seen: seen.clone()}));
+	drop(outer);
+	let _last = report::scoped(Box::new(Recorder{name: "last", seen: seen.clone()}));
+	report::report(&Exception::new("Cannot connect".to_string()));
+	assert_eq!(*seen.lock().unwrap(), vec!["last"]);
+}
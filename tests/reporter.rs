@@ -0,0 +1,49 @@
+//! Check the posting of exceptions by `HttpReporter`
+#![cfg(feature = "reporter")]
+extern crate exceptions;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use exceptions::prelude::*;
+use exceptions::sink::{HttpReporter, Sink};
+
+/// Serve one request on a local port, sending its body to the returned receiver
+fn serve_once() -> (String, mpsc::Receiver<String>) {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let endpoint = format!("http://{}/exceptions", listener.local_addr().unwrap());
+	let (sender, receiver) = mpsc::channel();
+	thread::spawn(move || {
+		let (stream, _) = listener.accept().unwrap();
+		let mut reader = BufReader::new(stream);
+		let mut length = 0;
+		loop {
+			let mut line = String::new();
+			reader.read_line(&mut line).unwrap();
+			if line == "\r\n" {
+				break;
+			}
+			if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+				length = value.trim().parse().unwrap();
+			}
+		}
+		let mut body = vec![0; length];
+		reader.read_exact(&mut body).unwrap();
+		reader.get_mut().write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n").unwrap();
+		sender.send(String::from_utf8(body).unwrap()).unwrap();
+	});
+	return (endpoint, receiver);
+}
+
+#[test]
+fn flushed_exceptions_are_posted_as_an_array() {
+	let (endpoint, bodies) = serve_once();
+	let reporter = HttpReporter::new(&endpoint);
+	reporter.write_trace(&Exception::new("Cannot connect".to_string()));
+	reporter.write_trace(&Exception::new("Cannot read".to_string()));
+	reporter.flush();
+	let body = bodies.recv().unwrap();
+	assert!(body.starts_with('['));
+	assert!(body.find("Cannot connect").unwrap() < body.find("Cannot read").unwrap());
+}
@@ -0,0 +1,100 @@
+//! Check the sinks writing stack traces
+extern crate exceptions;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use exceptions::prelude::*;
+use exceptions::config::{self, FormatConfig};
+use exceptions::sink::{FileSink, RateLimitedSink, Sink};
+
+/// Get a path in the temporary directory, unique to this process and to `name`
+fn temp_path(name: &str) -> PathBuf {
+	return env::temp_dir().join(format!("exceptions-{}-{}.log", name, process::id()));
+}
+
+#[test]
+fn file_sink_writes_without_colors() {
+	config::set(FormatConfig{color: true, force_color: true, ..FormatConfig::default()});
+	let path = temp_path("colors");
+	let sink = FileSink::new(&path).unwrap();
+	sink.write_trace(&Exception::new("Cannot connect".to_string()));
+	let written = fs::read_to_string(&path).unwrap();
+	let _ = fs::remove_file(&path);
+	assert!(written.contains("Cannot connect"));
+	assert!(!written.contains('\x1b'));
+}
+
+#[test]
+fn file_sink_recreates_a_removed_file() {
+	let path = temp_path("removed");
+	let sink = FileSink::new(&path).unwrap().max_size(1);
+	sink.write_trace(&Exception::new("Cannot connect".to_string()));
+	fs::remove_file(&path).unwrap();
+	sink.write_trace(&Exception::new("Cannot read".to_string()));
+	let written = fs::read_to_string(&path).unwrap();
+	let _ = fs::remove_file(&path);
+	assert!(written.contains("Cannot read"));
+}
+
+#[test]
+fn file_sink_keeps_writing_when_rotating_fails() {
+	let path = temp_path("unrotated");
+	// A rotated file can't replace a directory which isn't empty
+	let rotated = PathBuf::from(format!("{}.1", path.display()));
+	fs::create_dir_all(rotated.join("busy")).unwrap();
+	let sink = FileSink::new(&path).unwrap().max_size(1).keep(1);
+	sink.write_trace(&Exception::new("Cannot connect".to_string()));
+	sink.write_trace(&Exception::new("Cannot read".to_string()));
+	let written = fs::read_to_string(&path).unwrap();
+	let _ = fs::remove_file(&path);
+	let _ = fs::remove_dir_all(&rotated);
+	assert!(written.contains("Cannot connect"));
+	assert!(written.contains("Cannot read"));
+}
+
+/// `Sink` keeping the messages of the written traces
+struct MessagesSink(Arc<Mutex<Vec<String>>>);
+
+impl Sink for MessagesSink {
+	fn write_trace(&self, th: &Throwable) {
+		self.0.lock().unwrap().push(th.get_message().to_string());
+	}
+}
+
+#[test]
+fn rate_limited_sink_reports_suppressed_traces_after_the_window() {
+	let messages = Arc::new(Mutex::new(Vec::new()));
+	let sink = RateLimitedSink::new(MessagesSink(messages.clone()), Duration::from_millis(50));
+	for _ in 0..3 {
+		sink.write_trace(&Exception::new("Cannot connect".to_string()));
+	}
+	thread::sleep(Duration::from_millis(60));
+	sink.write_trace(&Exception::new("Cannot read".to_string()));
+	let messages = messages.lock().unwrap().clone();
+	assert_eq!(messages.len(), 3);
+	assert_eq!(messages[0], "Cannot connect");
+	assert!(messages[1].contains("2"));
+	assert!(messages[1].ends_with(": Cannot connect"));
+	assert_eq!(messages[2], "Cannot read");
+}
+
+#[test]
+fn rate_limited_sink_reports_suppressed_traces_without_new_writes() {
+	let messages = Arc::new(Mutex::new(Vec::new()));
+	let sink = RateLimitedSink::new(MessagesSink(messages.clone()), Duration::from_millis(20));
+	for port in 0..3 {
+		sink.write_trace(&Exception::new(format!("Cannot connect to port {}", port)));
+	}
+	thread::sleep(Duration::from_millis(200));
+	let written = messages.lock().unwrap().clone();
+	drop(sink);
+	assert_eq!(written.len(), 2);
+	assert_eq!(written[0], "Cannot connect to port 0");
+	assert!(written[1].contains("2"));
+	assert!(written[1].ends_with(": Cannot connect to port 0"));
+}
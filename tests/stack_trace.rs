@@ -0,0 +1,33 @@
+//! Check the entries recorded in stack traces
+#[macro_use]
+extern crate exceptions;
+
+use std::borrow::Cow;
+use exceptions::prelude::*;
+
+fn fail() -> Result<(), Exception> {
+	throw!("Cannot read");
+}
+
+fn load(path: &str) -> Result<(), Exception> {
+	with_context!("loading {}", path => fail());
+	return Ok(());
+}
+
+#[test]
+fn context_labels_are_owned() {
+	let e = load("config.toml").unwrap_err();
+	let label = e.find_frame(|f| f.expr.starts_with("loading")).unwrap();
+	assert_eq!(label.expr, "loading config.toml");
+	assert!(match label.expr { Cow::Owned(_) => true, Cow::Borrowed(_) => false });
+}
+
+#[test]
+fn boundary_labels_are_owned() {
+	let mut e = Exception::new("Cannot read".to_string());
+	e.push_boundary(&format!("task {}", 7));
+	let top = e.top_frame().unwrap();
+	assert!(top.is_boundary());
+	assert_eq!(top.expr, "task 7");
+	assert!(match top.expr { Cow::Owned(_) => true, Cow::Borrowed(_) => false });
+}
@@ -0,0 +1,82 @@
+//! Check the forms of `throw!`
+#[macro_use]
+extern crate exceptions;
+
+use exceptions::prelude::*;
+
+/// A `Throwable` not based on `Exception`, convertible from string literals
+pub struct ParseException {
+	message: &'static str,
+	entries: Vec<StackEntry>
+}
+
+impl Throwable for ParseException {
+	fn push_entry(&mut self, entry: StackEntry) {
+		self.entries.push(entry);
+	}
+	
+	fn get_message(&self) -> &str {
+		return self.message;
+	}
+	
+	fn get_cause(&self) -> Option<&Throwable> {
+		return None;
+	}
+}
+
+impl IntoThrowable<ParseException> for &'static str {
+	fn into_throwable(self) -> ParseException {
+		return ParseException{message: self, entries: Vec::new()};
+	}
+}
+
+fn throw_literal() -> Result<(), Exception> {
+	throw!("Empty input");
+}
+
+fn throw_custom_literal() -> Result<(), ParseException> {
+	throw!("Unexpected token");
+}
+
+#[test]
+fn literal_is_thrown_as_an_exception() {
+	let e = throw_literal().unwrap_err();
+	assert_eq!(e.get_message(), "Empty input");
+	assert_eq!(e.frame_count(), 1);
+}
+
+#[test]
+fn literal_is_converted_into_the_returned_throwable() {
+	let e = throw_custom_literal().unwrap_err();
+	assert_eq!(e.get_message(), "Unexpected token");
+	assert_eq!(e.entries.iter().map(|f| (&*f.file, f.line)).collect::<Vec<_>>(), vec![(file!(), 38)]);
+}
+
+fn parse_custom(text: &str) -> Result<(), ParseException> {
+	with_context!("parsing {}", text => throw_custom_literal());
+	return Ok(());
+}
+
+#[test]
+fn labels_are_pushed_as_owned_entries() {
+	let e = parse_custom("config.toml").unwrap_err();
+	assert_eq!(e.entries.last().unwrap().expr, "parsing config.toml");
+}
+
+/// Error codes are thrown as `ParseException`s
+impl IntoThrowable<ParseException> for i32 {
+	fn into_throwable(self) -> ParseException {
+		return ParseException{message: "Error code", entries: Vec::new()};
+	}
+}
+
+fn throw_code() -> Result<(), ParseException> {
+	throw!(42);
+}
+
+#[test]
+fn other_literals_are_converted_with_into_throwable() {
+	let e = throw_code().unwrap_err();
+	assert_eq!(e.get_message(), "Error code");
+	assert_eq!(e.entries.len(), 1);
+}
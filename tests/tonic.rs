@@ -0,0 +1,52 @@
+//! Check the conversions between `Exception` and `tonic::Status`
+#![cfg(feature = "tonic")]
+#[macro_use]
+extern crate exceptions;
+extern crate tonic;
+
+use exceptions::prelude::*;
+use tonic::{Code, Status};
+
+#[test]
+fn code_is_mapped_from_the_kind_first() {
+	let mut e = Exception::new("No such user".to_string());
+	e.set_kind("io.not_found");
+	e.set_http_status(500);
+	assert_eq!(Status::from(e).code(), Code::NotFound);
+}
+
+#[test]
+fn code_falls_back_to_the_http_status() {
+	let mut e = Exception::new("Slow down".to_string());
+	e.set_http_status(429);
+	assert_eq!(Status::from(e).code(), Code::ResourceExhausted);
+}
+
+fn find_user() -> Result<(), Exception> {
+	let cause = Exception::new("Connection reset".to_string());
+	let mut e = Exception::new_with_cause("No such user".to_string(), cause);
+	e.set_kind("io.not_found");
+	throw!(e);
+}
+
+#[test]
+fn exception_is_sent_in_the_details() {
+	let status = Status::from(find_user().unwrap_err());
+	assert!(!status.details().is_empty());
+	let e: Exception = status.into_throwable();
+	assert_eq!(e.get_message(), "No such user");
+	assert_eq!(e.frame_count(), 1);
+	assert_eq!(e.top_frame().unwrap().line, 29);
+	assert!(e.is_instance_of("io.not_found"));
+	assert_eq!(e.get_cause().unwrap().get_message(), "Connection reset");
+	assert_eq!(e.http_status(), 404);
+}
+
+#[test]
+fn foreign_details_are_ignored() {
+	let status = Status::with_details(Code::Unavailable, "Try later", vec![1, 2, 3].into());
+	let e: Exception = status.into_throwable();
+	assert_eq!(e.get_message(), "Try later");
+	assert!(e.get_cause().is_none());
+	assert_eq!(e.http_status(), 503);
+}
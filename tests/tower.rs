@@ -0,0 +1,47 @@
+//! Check the `tower` middleware
+#![cfg(feature = "tower")]
+extern crate exceptions;
+extern crate tower_layer;
+extern crate tower_service;
+
+use std::future::{self, Future, Ready};
+use std::pin::Pin;
+use std::task::{Context, Poll, Waker};
+use exceptions::prelude::*;
+use exceptions::ExceptionLayer;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// `Service` failing every request
+struct Failing;
+
+impl Service<()> for Failing {
+	type Response = ();
+	type Error = Exception;
+	type Future = Ready<Result<(), Exception>>;
+	
+	fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Exception>> {
+		return Poll::Ready(Ok(()));
+	}
+	
+	fn call(&mut self, _request: ()) -> Ready<Result<(), Exception>> {
+		return future::ready(Err(Exception::new("Cannot connect".to_string())));
+	}
+}
+
+#[test]
+fn failures_are_recorded_as_a_boundary() {
+	let layer = ExceptionLayer::<Exception, _>::new(|e: Exception, _id: u64| e);
+	let mut service = layer.layer(Failing);
+	let mut response = service.call(());
+	let poll = Pin::new(&mut response).poll(&mut Context::from_waker(Waker::noop()));
+	let e = match poll {
+		Poll::Ready(Err(e)) => e,
+		_ => panic!("The request should fail")
+	};
+	let cause = e.get_cause().unwrap();
+	assert_eq!(cause.frame_count(), 1);
+	let top = cause.top_frame().unwrap();
+	assert!(top.is_boundary());
+	assert_eq!(top.expr, "ExceptionService::call()");
+}